@@ -1,24 +1,16 @@
 #!/usr/bin/env python
 import sys
 from util import Timer
-from importlib.util import find_spec
 
 from fast_dep import GraphBuilder
 
 module = sys.argv[1]
-spec = find_spec(module)
-target = spec.origin
 
 timer = Timer()
 with timer:
     # Do it!
     b = GraphBuilder()
-    with open(spec.origin) as f:
-        source = f.read()
-    graph = b.build(
-        source=source,
-        package=spec.parent
-    )
+    graph = b.build_module(module)
 
 print(f"Traced module '{module}': {timer.latest_cumtime}")
 print(f'\tGraph Size: {graph.size()}')
\ No newline at end of file
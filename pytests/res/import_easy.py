@@ -121,5 +121,67 @@ IMPORTS = [
                 { 'name': 'blah' },
             ]
         }]
+    },
+    {
+        'source' :"""
+        from os import *
+        """,
+        # ----------------
+        'expect' : [{
+            'type': 'import_from',
+            'level': 0,
+            'module': 'os',
+            'names': [
+                { 'name': '*' },
+            ]
+        }]
+    },
+    {
+        'source' :"""
+        from .. import *
+        """,
+        # ----------------
+        'expect' : [{
+            'type': 'import_from',
+            'level': 2,
+            'module': '',
+            'names': [
+                { 'name': '*' },
+            ]
+        }]
+    },
+    {
+        'source' :"""
+        plugin = importlib.import_module("pkg.plugins.foo")
+        """,
+        # ----------------
+        'expect' : [{
+            'type': 'dynamic',
+            'function': 'importlib.import_module',
+            'name': 'pkg.plugins.foo',
+        }]
+    },
+    {
+        'source' :"""
+        sibling = import_module('.sibling', package='pkg')
+        """,
+        # ----------------
+        'expect' : [{
+            'type': 'dynamic',
+            'function': 'import_module',
+            'name': '.sibling',
+            'package': 'pkg',
+        }]
+    },
+    {
+        'source' :"""
+        json = __import__("json")
+        """,
+        # ----------------
+        'expect' : [{
+            'type': 'dynamic',
+            'function': '__import__',
+            'name': 'json',
+        }]
     }
-]
\ No newline at end of file
+]
@@ -59,4 +59,26 @@ NOT_IMPORTS = [
         # ----------------
         'expect' : []
     },
+    {
+        'source' :"""
+        importlib.import_module(name)  # importlib.import_module("in_comment")
+        print("importlib.import_module('in_string')")
+        finder.find_spec("method")
+        """,
+        # ----------------
+        'expect' : []
+    },
+    {
+        'source' :"""
+        doc = \"\"\"
+        importlib.import_module("in_docstring")
+        import os
+        \"\"\"
+        usage = f'''
+        __import__('in_f_string')
+        '''
+        """,
+        # ----------------
+        'expect' : []
+    },
 ]
\ No newline at end of file
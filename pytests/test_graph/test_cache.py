@@ -0,0 +1,19 @@
+import os
+import sys
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+from fast_dep import GraphBuilder
+
+def test_cache_dir(tmp_path):
+    cache_dir = str(tmp_path / 'cache')
+
+    first = GraphBuilder(cache_dir=cache_dir).build('import diamond_top\n')
+    assert os.path.isfile(os.path.join(cache_dir, 'fast_dep_cache.json'))
+
+    second = GraphBuilder(cache_dir=cache_dir).build('import diamond_top\n')
+    assert second.to_json() == first.to_json()
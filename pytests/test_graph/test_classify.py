@@ -0,0 +1,48 @@
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+def test_categories():
+    builder = GraphBuilder()
+    graph = builder.build('import sys\nimport json\nimport diamond_top\nimport nothing')
+
+    assert graph.get('sys').category == 'built_in'
+    assert graph.get('json').category == 'stdlib'
+    assert graph.get('diamond_top').category == 'first_party'
+    assert graph.get('nothing').category == 'unknown'
+
+def test_project_roots():
+    builder = GraphBuilder(project_roots=[os.path.join(RES_DIR, 'app')])
+    graph = builder.build('import app.good\nimport diamond_top')
+
+    assert graph.get('app.good').category == 'first_party'
+    assert graph.get('diamond_top').category == 'third_party'
+
+def test_by_category():
+    builder = GraphBuilder()
+    graph = builder.build('import json\nimport diamond_top')
+
+    assert 'json.decoder' in graph.by_category('stdlib')
+    assert 'diamond_top' not in graph.by_category('stdlib')
+
+    with pytest.raises(ValueError):
+        graph.by_category('nothing')
+
+def test_filter_categories():
+    builder = GraphBuilder()
+    graph = builder.build('import json\nimport diamond_top')
+    filtered = graph.filter_categories(['first_party'])
+
+    assert 'json' not in filtered.keys()
+    assert 'diamond_bottom' in filtered.keys()
+    assert 'json' not in filtered.get('<terminal>').edges
@@ -0,0 +1,28 @@
+import os
+
+import pytest
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+CONTRACTS_DIR = os.path.abspath(os.path.join(THIS_DIR, '../../tests/res/contracts'))
+
+def test_check_contracts():
+    builder = GraphBuilder()
+    graph = builder.build_project(CONTRACTS_DIR)
+
+    violations = graph.check_contracts(os.path.join(CONTRACTS_DIR, 'contracts.toml'))
+    assert [(violation.contract, violation.path) for violation in violations] == [
+        ('Domain is independent of the web', ['app.domain.model', 'app.shared.util', 'app.web.views']),
+        ('Layers', ['app.storage.db', 'app.api.routes']),
+        ('Plugins are independent', ['app.plugins.a', 'app.plugins.b']),
+    ]
+    assert violations[1].edges[0].line == 2
+
+def test_missing_contracts():
+    builder = GraphBuilder()
+    graph = builder.build_project(CONTRACTS_DIR)
+
+    with pytest.raises(fast_dep.LoadError):
+        graph.check_contracts(os.path.join(CONTRACTS_DIR, 'nothing.toml'))
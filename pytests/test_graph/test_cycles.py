@@ -0,0 +1,29 @@
+import os
+import sys
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+from fast_dep import GraphBuilder
+
+def test_cycles():
+    builder = GraphBuilder()
+    graph = builder.build('import cycle_a\nimport acyclic\n')
+
+    cycles = graph.cycles()
+    assert [c.modules for c in cycles] == [['cycle_a', 'cycle_b', 'cycle_c']]
+    assert cycles[0].path is None
+
+    cycle, = graph.cycles(shortest=True)
+    assert cycle.path == ['cycle_a', 'cycle_b', 'cycle_a']
+    assert [e.line for e in cycle.edges] == [1, 2]
+
+def test_components_cover_graph():
+    builder = GraphBuilder()
+    graph = builder.build('import cycle_a\n')
+
+    components = graph.strongly_connected_components()
+    assert sum(len(c) for c in components) == graph.size()
@@ -0,0 +1,34 @@
+import json
+import os
+import sys
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+from fast_dep import DepGraph, GraphBuilder
+
+def test_diff():
+    base = GraphBuilder().build_module('diamond_top')
+    head = GraphBuilder().build_module('diamond_right')
+
+    diff = base.diff(head)
+    assert diff
+    assert diff.added_nodes == []
+    assert diff.removed_nodes == ['diamond_top']
+    assert diff.removed_edges == [('diamond_top', 'diamond_left'), ('diamond_top', 'diamond_right')]
+    assert [(change.name, change.old, change.new) for change in diff.depth_changes] == [
+        ('diamond_bottom', 2, 1),
+        ('diamond_right', 1, 0),
+    ]
+    assert not base.diff(base)
+
+def test_diff_loaded():
+    base = GraphBuilder().build_module('diamond_top')
+    head = GraphBuilder().build_module('diamond_right')
+
+    loaded = DepGraph.from_json(base.to_json()).diff(DepGraph.from_json(head.to_json()))
+    assert loaded.to_text() == base.diff(head).to_text()
+    assert json.loads(loaded.to_json())['removed_nodes'] == ['diamond_top']
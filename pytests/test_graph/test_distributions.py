@@ -0,0 +1,32 @@
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+SITE_DIR = os.path.abspath(os.path.join(THIS_DIR, '../../tests/res/site-packages'))
+sys.path.append(SITE_DIR)
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+def test_distribution():
+    builder = GraphBuilder()
+    graph = builder.build('import fakepkg\nimport diamond_left')
+
+    distribution = graph.get('fakepkg.sub').distribution
+    assert (distribution.name, distribution.version) == ('FakePkg', '1.2.0')
+    assert graph.get('legacy_mod').distribution.name == 'legacy'
+    assert graph.get('unowned').distribution is None
+    assert graph.get('diamond_left').distribution is None
+
+def test_distributions():
+    builder = GraphBuilder()
+    graph = builder.build('import fakepkg')
+
+    assert [d.name for d in graph.distributions()] == ['FakePkg', 'legacy']
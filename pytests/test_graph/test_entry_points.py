@@ -0,0 +1,43 @@
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+def test_build_module():
+    builder = GraphBuilder()
+    graph = builder.build_module('app.edges')
+
+    assert '<terminal>' not in graph.keys()
+    assert graph.get('app.edges').depth == 0
+    assert 'app.only_broken' in graph.keys()
+
+    with pytest.raises(fast_dep.ResolutionError):
+        builder.build_module('does_not_exist')
+
+def test_build_file():
+    builder = GraphBuilder()
+    graph = builder.build_file(os.path.join(RES_DIR, 'app', 'edges.py'))
+
+    assert graph.get('app.edges').depth == 0
+
+    with pytest.raises(fast_dep.LoadError):
+        builder.build_file(os.path.join(RES_DIR, 'does_not_exist.py'))
+
+def test_build_many():
+    builder = GraphBuilder()
+    graph = builder.build_many(['diamond_top', 'cycle_a', os.path.join(RES_DIR, 'app', 'edges.py')])
+
+    assert graph.roots() == ['diamond_top', 'cycle_a', 'app.edges']
+    assert graph.get('cycle_a').depth == 0
+    assert 'diamond_bottom' in graph.reachable_from('diamond_top')
+    assert 'diamond_bottom' not in graph.reachable_from('cycle_a')
+    assert graph.depths_from('diamond_top')['diamond_bottom'] == 2
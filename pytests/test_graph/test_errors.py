@@ -0,0 +1,44 @@
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+def test_errors_recorded():
+    builder = GraphBuilder()
+    graph = builder.build('import app\n')
+
+    errors = dict(graph.errors())
+    assert isinstance(errors['app.broken'], fast_dep.ResolutionError)
+    assert isinstance(errors['app.binary'], fast_dep.LoadError)
+    assert 'app.only_broken' in graph.keys()
+
+def test_missing_node():
+    builder = GraphBuilder()
+    graph = builder.build('import app\n')
+
+    with pytest.raises(fast_dep.GraphError):
+        graph.get('does_not_exist')
+
+def test_unresolved():
+    builder = GraphBuilder()
+    graph = builder.build('import app.missing\n')
+
+    unresolved = {
+        (u.name, u.importer, u.text) for u in graph.unresolved()
+    }
+    assert unresolved == {
+        ('not_installed', 'app.missing', 'not_installed'),
+        ('app.not_here', 'app.missing', '.not_here'),
+        ('app.helper.nope', 'app.missing', 'app.helper.nope'),
+    }
+    assert graph.get('not_installed').missing
+    assert not graph.get('app.missing').missing
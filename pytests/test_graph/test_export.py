@@ -0,0 +1,42 @@
+import os
+import sys
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+from fast_dep import GraphBuilder
+
+def test_dot():
+    builder = GraphBuilder()
+    graph = builder.build('import diamond_top\n')
+
+    dot = graph.to_dot(max_depth=1)
+    assert dot.startswith('digraph dependencies {')
+    assert '"<terminal>" -> "diamond_top";' in dot
+    assert 'diamond_bottom' not in dot
+
+def test_mermaid():
+    builder = GraphBuilder()
+    graph = builder.build('import app.helper\n')
+
+    mermaid = graph.to_mermaid(collapse_packages=True, color=True)
+    assert mermaid.startswith('flowchart LR')
+    assert 'app.helper' not in mermaid
+    assert 'classDef' in mermaid
+
+def test_tree():
+    builder = GraphBuilder()
+    graph = builder.build_module('diamond_top')
+
+    assert graph.to_tree() == (
+        'diamond_top\n'
+        '├── diamond_left\n'
+        '│   └── diamond_bottom\n'
+        '└── diamond_right\n'
+        '    ├── diamond_bottom\n'
+        '    └── diamond_left (*)\n'
+    )
+    assert graph.to_tree(max_depth=1) == 'diamond_top\n├── diamond_left\n└── diamond_right\n'
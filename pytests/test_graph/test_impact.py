@@ -0,0 +1,43 @@
+import os
+
+from fast_dep import GraphBuilder
+
+pytest_plugins = ['pytester']
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+IMPACT_DIR = os.path.abspath(os.path.join(THIS_DIR, '../../tests/res/impact'))
+
+def changed(*files):
+    return [os.path.join(IMPACT_DIR, file) for file in files]
+
+def test_affected_tests():
+    builder = GraphBuilder()
+    graph = builder.build_project(IMPACT_DIR)
+
+    assert graph.modules_for_files(changed('pkg/b.py', 'README.md')) == ['pkg.b']
+    assert graph.affected_tests(changed('pkg/b.py')) == ['tests.b_test', 'tests.test_a']
+    assert graph.affected_tests(changed('pkg/b.py'), patterns=['test_*.py']) == ['tests.test_a']
+    assert graph.affected_tests(changed('pkg/__init__.py')) == ['tests.b_test', 'tests.test_a', 'tests.test_c']
+    assert graph.affected_modules(['pkg.a']) == {'pkg.a', 'tests.test_a'}
+
+def test_plugin(pytester):
+    pytester.syspathinsert()
+    pytester.mkpydir('pkg')
+    pytester.makepyfile(**{
+        'pkg/a': 'VALUE = 1\n',
+        'pkg/b': 'VALUE = 2\n',
+        'test_a': 'from pkg.a import VALUE\ndef test_a():\n    assert VALUE == 1\n',
+        'test_b': 'import pkg.b\ndef test_b():\n    assert pkg.b.VALUE == 2\n',
+    })
+
+    result = pytester.runpytest('--affected-by', 'pkg/a.py')
+    result.assert_outcomes(passed=1, deselected=1)
+    result.stdout.fnmatch_lines(['*test_a.py .*'])
+
+    result = pytester.runpytest('--affected-by', 'pkg/__init__.py')
+    result.assert_outcomes(passed=2)
+
+    # Importers of a deleted module are unknown, nothing is deselected
+    os.remove(os.path.join(str(pytester.path), 'pkg', 'a.py'))
+    result = pytester.runpytest('--affected-by', 'pkg/a.py', '--continue-on-collection-errors')
+    result.assert_outcomes(passed=1, errors=1)
@@ -0,0 +1,17 @@
+import os
+import sys
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+from fast_dep import GraphBuilder
+
+def test_same_graph_as_serial():
+    source = 'import app\nimport diamond_top\nimport cycle_a\nimport json\n'
+
+    serial = GraphBuilder().build(source)
+    parallel = GraphBuilder(parallel=True, threads=2).build(source)
+    assert parallel.to_json() == serial.to_json()
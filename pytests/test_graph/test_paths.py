@@ -0,0 +1,33 @@
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+def test_path():
+    builder = GraphBuilder()
+    graph = builder.build('import diamond_top\n')
+
+    assert graph.path('<terminal>', 'diamond_bottom') == [
+        '<terminal>', 'diamond_top', 'diamond_left', 'diamond_bottom'
+    ]
+    assert graph.path('diamond_bottom', 'diamond_top') is None
+
+    with pytest.raises(fast_dep.GraphError):
+        graph.path('<terminal>', 'does_not_exist')
+
+def test_all_paths():
+    builder = GraphBuilder()
+    graph = builder.build('import diamond_top\n')
+
+    paths = graph.all_paths('diamond_top', 'diamond_bottom')
+    assert len(paths) == 3
+    assert graph.all_paths('diamond_top', 'diamond_bottom', limit=1) == paths[:1]
@@ -0,0 +1,37 @@
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+PROJECT_DIR = os.path.abspath(os.path.join(THIS_DIR, '../../tests/res/project'))
+
+def test_build_project():
+    builder = GraphBuilder()
+    graph = builder.build_project(PROJECT_DIR)
+
+    assert 'proj.ns.plugin' in graph.roots()
+    assert graph.get('proj.core').depth == 0
+    assert 'tests.test_core' in graph.get('proj.core').dependents
+    assert [name for name, _ in graph.errors()] == ['tests.broken']
+
+def test_orphans():
+    builder = GraphBuilder()
+    graph = builder.build_project(PROJECT_DIR)
+
+    assert 'proj.unused' in graph.orphans()
+    assert 'proj.util' not in graph.orphans()
+
+def test_missing_project():
+    builder = GraphBuilder()
+
+    with pytest.raises(fast_dep.LoadError):
+        builder.build_project(os.path.join(PROJECT_DIR, 'nothing'))
@@ -0,0 +1,46 @@
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+PROJECT_DIR = os.path.abspath(os.path.join(THIS_DIR, '../../tests/res/deps_project'))
+SITE_DIR = os.path.abspath(os.path.join(THIS_DIR, '../../tests/res/site-packages'))
+sys.path.append(SITE_DIR)
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+PYPROJECT = os.path.join(PROJECT_DIR, 'pyproject.toml')
+
+def test_check_dependencies():
+    builder = GraphBuilder()
+    graph = builder.build_project(PROJECT_DIR)
+    report = graph.check_dependencies(PYPROJECT)
+
+    assert report
+    [undeclared] = report.undeclared
+    assert undeclared.distribution == 'legacy'
+    assert undeclared.importer == 'app.main'
+    assert undeclared.edge.line == 4
+    assert report.unused == ['unused_dist']
+
+def test_check_with_requirements():
+    builder = GraphBuilder()
+    graph = builder.build_project(PROJECT_DIR)
+    report = graph.check_dependencies(PYPROJECT, [os.path.join(PROJECT_DIR, 'requirements.txt')])
+
+    assert report.undeclared == []
+    assert report.unused == ['unused_dist']
+
+def test_missing_pyproject():
+    builder = GraphBuilder()
+    graph = builder.build_project(PROJECT_DIR)
+
+    with pytest.raises(fast_dep.LoadError):
+        graph.check_dependencies(os.path.join(PROJECT_DIR, 'nothing.toml'))
@@ -0,0 +1,33 @@
+import json
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+import fast_dep
+from fast_dep import DepGraph, GraphBuilder
+
+def test_round_trip():
+    builder = GraphBuilder()
+    graph = builder.build('import app\nimport app.missing\n')
+
+    data = graph.to_json()
+    loaded = DepGraph.from_json(data)
+    assert loaded.keys() == graph.keys()
+    assert loaded.to_json() == data
+    # Errors are written sorted, so only their build order is lost
+    assert sorted(name for name, _ in loaded.errors()) == sorted(name for name, _ in graph.errors())
+
+    document = json.loads(graph.to_json(pretty=True))
+    assert document['version'] == 1
+    assert {node['name'] for node in document['nodes']} == graph.keys()
+
+def test_invalid_document():
+    with pytest.raises(fast_dep.SerializationError):
+        DepGraph.from_json('{"version": 0, "nodes": [], "edges": []}')
@@ -0,0 +1,56 @@
+import os
+import sys
+
+import pytest
+
+THIS_DIR = os.path.abspath(os.path.dirname(__file__))
+RES_DIR = os.path.abspath(
+    os.path.join(THIS_DIR, '../../tests/res/builder')
+)
+sys.path.append(RES_DIR)
+
+import fast_dep
+from fast_dep import GraphBuilder
+
+def test_exclude():
+    builder = GraphBuilder(exclude=['diamond_left', 'app.*'])
+    graph = builder.build('import diamond_top\nimport app.edges')
+
+    assert 'diamond_left' not in graph.keys()
+    assert 'app.edges' not in graph.keys()
+    assert 'app' in graph.keys()
+
+def test_stop_at():
+    builder = GraphBuilder(stop_at=['diamond_right'])
+    graph = builder.build('import diamond_top')
+
+    assert graph.get('diamond_right').dependencies == 0
+    assert 'diamond_bottom' in graph.keys()
+
+def test_stop_at_stdlib_package():
+    builder = GraphBuilder(stop_at=['email'])
+    graph = builder.build('import email.message')
+
+    assert graph.get('email.message').dependencies == 0
+
+def test_max_depth():
+    builder = GraphBuilder(max_depth=3)
+    graph = builder.build_module('depth_top')
+
+    assert graph.get('depth_leaf').depth == 2
+    assert graph.get('depth_end').depth == 3
+    assert 'acyclic' not in graph.keys()
+
+def test_star_import():
+    builder = GraphBuilder()
+    graph = builder.build_module('star_user')
+
+    assert set(graph.get('star_user').edges) == {'star_pkg', 'star_pkg.alpha', 'star_pkg.beta'}
+
+def test_dynamic_imports():
+    graph = GraphBuilder().build_module('plugin_host')
+    assert 'plugins.alpha' not in graph.keys()
+
+    graph = GraphBuilder(follow_dynamic=True).build_module('plugin_host')
+    assert {'plugins', 'plugins.alpha', 'plugins.beta', 'plugins.gamma'} <= set(graph.keys())
+    assert graph.get('plugin_host').edges['plugins.alpha'][0].kind == 'dynamic'
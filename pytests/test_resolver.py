@@ -0,0 +1,38 @@
+import pkgutil
+import importlib.util
+
+import fast_dep
+resolver = fast_dep.resolver
+
+NAMES = [
+    'os',
+    'os.path',
+    'sys',
+    'json',
+    'json.decoder',
+    'collections.abc',
+    'importlib.machinery',
+    'xml.etree.ElementTree',
+    'does_not_exist',
+    'does_not_exist.sub',
+]
+
+def _expected(name):
+    try:
+        spec = importlib.util.find_spec(name)
+    except ModuleNotFoundError:
+        spec = None
+
+    if spec is None:
+        return None
+    return {'name': spec.name, 'origin': spec.origin}
+
+def test_matches_importlib():
+    for name in NAMES:
+        assert resolver.find_spec(name) == _expected(name)
+
+def test_matches_importlib_top_level():
+    # `distutils` is redirected by setuptools through a custom meta path finder
+    names = [m.name for m in pkgutil.iter_modules() if m.name != 'distutils']
+    for name in names:
+        assert resolver.find_spec(name) == _expected(name)
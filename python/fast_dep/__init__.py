@@ -0,0 +1,2 @@
+from .fast_dep import *
+from .fast_dep import __all__
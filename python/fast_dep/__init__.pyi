@@ -0,0 +1,198 @@
+from typing import Any, Dict, List, FrozenSet, Tuple
+from typing import Optional
+
+# Exceptions
+class FastDepError(Exception): ...
+class ParseError(FastDepError): ...
+class ResolutionError(FastDepError): ...
+class LoadError(FastDepError): ...
+class GraphError(FastDepError): ...
+class SerializationError(FastDepError): ...
+
+# Parser module
+class parser:
+    def parse(source: str) -> List[dict]: ... # `from x import *` has a single name, `*`, literal calls like `importlib.import_module("x")` are of type "dynamic"
+
+# Resolver module
+class resolver:
+    def find_spec(name: str) -> Optional[dict]: ...
+
+class ImportEdge:
+    @property
+    def file(self) -> Optional[str]: ...
+    @property
+    def line(self) -> int: ...
+    @property
+    def column(self) -> int: ...
+    @property
+    def kind(self) -> str: ... # "import", "import_from" or "dynamic"
+    @property
+    def asname(self) -> Optional[str]: ...
+    @property
+    def level(self) -> int: ...
+    @property
+    def scope(self) -> str: ... # "module", "class" or "function"
+    @property
+    def conditional(self) -> bool: ...
+    @property
+    def type_checking(self) -> bool: ...
+
+class Distribution:
+    @property
+    def name(self) -> str: ...
+    @property
+    def version(self) -> str: ...
+
+class DepNode:
+    # Derived
+    @property
+    def name(self) -> str: ...
+    @property
+    def spec(self) -> Any: ... # TODO: Update
+    @property
+    def dependents(self) -> FrozenSet[str]: ...
+    @property
+    def depth(self) -> Optional[int]: ...
+    @property
+    def missing(self) -> bool: ...
+    @property
+    def distribution(self) -> Optional[Distribution]: ... # Only for third-party modules
+
+    # Custom
+    @property
+    def dependencies(self) -> int: ...
+    @property
+    def edges(self) -> Dict[str, List[ImportEdge]]: ...
+    @property
+    def category(self) -> str: ... # "stdlib", "third_party", "first_party", "built_in", "extension" or "unknown"
+
+class UnresolvedImport:
+    @property
+    def name(self) -> str: ...
+    @property
+    def importer(self) -> str: ...
+    @property
+    def text(self) -> str: ...
+
+class ImportCycle:
+    @property
+    def modules(self) -> List[str]: ...
+    @property
+    def path(self) -> Optional[List[str]]: ... # Only set with `shortest=True`, first module repeated at the end
+    @property
+    def edges(self) -> Optional[List[ImportEdge]]: ...
+    def __len__(self) -> int: ...
+
+class UndeclaredImport:
+    @property
+    def distribution(self) -> str: ...
+    @property
+    def module(self) -> str: ...
+    @property
+    def importer(self) -> str: ...
+    @property
+    def edge(self) -> ImportEdge: ...
+
+class DependencyReport:
+    @property
+    def undeclared(self) -> List[UndeclaredImport]: ...
+    @property
+    def unused(self) -> List[str]: ...
+    def __bool__(self) -> bool: ... # Whether any problem was found
+
+class ContractViolation:
+    @property
+    def contract(self) -> str: ... # Name of the broken contract
+    @property
+    def path(self) -> List[str]: ... # Import chain, ex: `[a, b, c]` for `a -> b -> c`
+    @property
+    def edges(self) -> List[ImportEdge]: ...
+
+class DepthChange:
+    @property
+    def name(self) -> str: ...
+    @property
+    def old(self) -> Optional[int]: ... # None when unreachable from the roots
+    @property
+    def new(self) -> Optional[int]: ...
+
+class GraphDiff:
+    @property
+    def added_nodes(self) -> List[str]: ...
+    @property
+    def removed_nodes(self) -> List[str]: ...
+    @property
+    def added_edges(self) -> List[Tuple[str, str]]: ...
+    @property
+    def removed_edges(self) -> List[Tuple[str, str]]: ...
+    @property
+    def depth_changes(self) -> List[DepthChange]: ...
+    def to_text(self) -> str: ...
+    def to_json(self, pretty: Optional[bool] = False) -> str: ...
+    def __bool__(self) -> bool: ... # Whether anything changed
+
+class DepGraph:
+    def size(self) -> int: ...
+    def num_dependencies(self) -> int: ...
+
+    def keys(self) -> FrozenSet[str]: ...
+    def get(self, name: str) -> DepNode: ...
+    def get_all_scoped(self, scope: str) -> List[DepNode]: ...
+    def origins(self) -> List[str]: ...
+    def names(self) -> List[str]: ...
+    def errors(self) -> List[Tuple[str, FastDepError]]: ...
+    def unresolved(self) -> List[UnresolvedImport]: ...
+    def to_json(self, pretty: Optional[bool] = False) -> str: ...
+    @staticmethod
+    def from_json(data: str) -> DepGraph: ...
+    def to_dot(
+        self,
+        max_depth: Optional[int] = None,
+        collapse_packages: Optional[bool] = False,
+        color: Optional[bool] = False
+    ) -> str: ...
+    def to_mermaid(
+        self,
+        max_depth: Optional[int] = None,
+        collapse_packages: Optional[bool] = False,
+        color: Optional[bool] = False
+    ) -> str: ...
+    def to_tree(self, max_depth: Optional[int] = None) -> str: ...
+    def roots(self) -> List[str]: ...
+    def orphans(self) -> List[str]: ...
+    def diff(self, other: DepGraph) -> GraphDiff: ... # Changes from this graph to `other`
+    def modules_for_files(self, files: List[str]) -> List[str]: ...
+    def affected_modules(self, modules: List[str]) -> FrozenSet[str]: ...
+    def affected_tests(self, files: List[str], patterns: Optional[List[str]] = None) -> List[str]: ...
+    def distributions(self) -> List[Distribution]: ...
+    def check_contracts(self, path: str) -> List[ContractViolation]: ... # Contracts from a TOML file
+    def check_dependencies(self, pyproject: str, requirements: Optional[List[str]] = None) -> DependencyReport: ...
+    def by_category(self, category: str) -> List[str]: ...
+    def filter_categories(self, categories: List[str]) -> DepGraph: ...
+    def depths_from(self, root: str) -> Dict[str, int]: ...
+    def reachable_from(self, root: str) -> FrozenSet[str]: ...
+    def path(self, from_: str, to: str) -> Optional[List[str]]: ...
+    def all_paths(self, from_: str, to: str, limit: Optional[int] = 10) -> List[List[str]]: ...
+    def cycles(self, shortest: Optional[bool] = False) -> List[ImportCycle]: ...
+    def strongly_connected_components(self) -> List[List[str]]: ...
+
+class GraphBuilder:
+    def __init__(
+        self,
+        verbose: Optional[bool] = False,
+        eager_only: Optional[bool] = False,
+        exclude_type_checking: Optional[bool] = False,
+        follow_dynamic: Optional[bool] = False, # Follow `importlib.import_module("...")` and similar literal calls
+        cache_dir: Optional[str] = None, # Parsed imports & specs are persisted here between processes
+        parallel: Optional[bool] = False,
+        threads: Optional[int] = 0, # Worker threads for parallel builds, 0 for one per CPU
+        exclude: Optional[List[str]] = None, # Module prefixes (`numpy`) or globs (`*.tests`) never added
+        stop_at: Optional[List[str]] = None, # Same patterns, added without following their imports
+        max_depth: Optional[int] = None,
+        project_roots: Optional[List[str]] = None # Only modules under these are first-party, all are without any
+    ): ...
+    def build(self, source: str, package: Optional[str] = None) -> DepGraph: ...
+    def build_module(self, name: str) -> DepGraph: ...
+    def build_file(self, path: str) -> DepGraph: ...
+    def build_many(self, entries: List[str]) -> DepGraph: ... # Module names or paths (containing a separator or ending in `.py`)
+    def build_project(self, path: str) -> DepGraph: ... # Every module under `path` is a root, see `DepGraph.orphans`
@@ -0,0 +1,89 @@
+"""Only run the tests which could be affected by a set of changed files.
+
+    pytest --affected-by src/pkg/module.py --affected-by src/pkg/other.py
+    pytest --affected-since origin/main
+
+Every module under the root directory is built, test modules which do not import a changed module (directly or not) are
+deselected. Test files missing from the graph always run. All tests run when a `conftest.py` changed, or when a changed
+Python file under the root is not in the graph (deleted or renamed, its importers can't be found any more). Changes to
+non-Python files (data, configuration) are not tracked.
+"""
+import os
+import subprocess
+
+from .fast_dep import GraphBuilder
+
+
+def pytest_addoption(parser):
+    group = parser.getgroup('fast-dep', 'test selection by imports')
+    group.addoption(
+        '--affected-by',
+        action='append',
+        default=[],
+        metavar='PATH',
+        help='Only run tests importing this file, directly or not. Can be repeated.'
+    )
+    group.addoption(
+        '--affected-since',
+        metavar='REF',
+        default=None,
+        help='Only run tests importing files changed since this git revision (working tree included).'
+    )
+
+
+def _key(path):
+    return os.path.normcase(os.path.realpath(path))
+
+
+def _changed_files(config):
+    changed = [os.path.abspath(path) for path in config.getoption('affected_by')]
+
+    ref = config.getoption('affected_since')
+    if ref is not None:
+        def git(*args, cwd):
+            return subprocess.run(
+                ['git', *args], cwd=cwd, check=True, stdout=subprocess.PIPE, universal_newlines=True
+            ).stdout
+
+        top_level = git('rev-parse', '--show-toplevel', cwd=str(config.rootpath)).strip()
+        for line in git('diff', '--name-only', ref, cwd=top_level).splitlines():
+            if line:
+                changed.append(os.path.join(top_level, line))
+
+    return changed
+
+
+def pytest_collection_modifyitems(session, config, items):
+    if not config.getoption('affected_by') and config.getoption('affected_since') is None:
+        return
+
+    changed = _changed_files(config)
+    if any(os.path.basename(path) == 'conftest.py' for path in changed):
+        return
+
+    root = str(config.rootpath)
+    graph = GraphBuilder(project_roots=[root]).build_project(root)
+
+    known = {_key(origin) for origin in graph.origins()}
+    root_prefix = os.path.join(_key(root), '')
+    for path in changed:
+        path = _key(path)
+        if path.endswith('.py') and path.startswith(root_prefix) and path not in known:
+            return
+
+    affected = {
+        _key(graph.get(name).spec['origin'])
+        for name in graph.affected_tests(changed, config.getini('python_files'))
+    }
+
+    selected, deselected = [], []
+    for item in items:
+        path = _key(str(item.fspath))
+        if path in known and path not in affected:
+            deselected.append(item)
+        else:
+            selected.append(item)
+
+    if deselected:
+        config.hook.pytest_deselected(items=deselected)
+        items[:] = selected
@@ -0,0 +1,372 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use log::{LevelFilter, Metadata, Record};
+
+use fast_dep::core::*;
+use fast_dep::error::FastDepError;
+use fast_dep::importlib::Resolver;
+
+/// Python import graphs from the command line.
+#[derive(Parser)]
+#[command(name = "fast-dep", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Log progress on stderr, repeat for debug output
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build a graph and print a summary of it
+    Build {
+        #[command(flatten)]
+        graph: GraphArgs,
+    },
+    /// Print the imports of the roots as a tree
+    Tree {
+        #[command(flatten)]
+        graph: GraphArgs,
+        /// Levels of the tree to print
+        #[arg(long)]
+        depth: Option<usize>,
+    },
+    /// Write the graph as JSON, Graphviz DOT or Mermaid
+    Export {
+        #[command(flatten)]
+        graph: GraphArgs,
+        #[arg(long, value_enum, default_value = "json")]
+        format: Format,
+        /// Written to stdout without one
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Indent JSON
+        #[arg(long)]
+        pretty: bool,
+        /// Leave out modules further than this from the roots (DOT / Mermaid)
+        #[arg(long)]
+        depth: Option<i32>,
+        /// Draw every top-level package as a single node (DOT / Mermaid)
+        #[arg(long)]
+        collapse_packages: bool,
+        /// Fill modules by category (DOT / Mermaid)
+        #[arg(long)]
+        color: bool,
+    },
+    /// Print the shortest import chain between two modules, exits with 1 when there is none
+    Path {
+        #[command(flatten)]
+        graph: GraphArgs,
+        #[arg(long)]
+        from: String,
+        #[arg(long)]
+        to: String,
+        /// Print every chain without repeated modules, shortest first
+        #[arg(long)]
+        all: bool,
+        /// Chains printed with `--all`
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Print import cycles, exits with 1 when there are any
+    Cycles {
+        #[command(flatten)]
+        graph: GraphArgs,
+        /// Print the shortest cycle through each group of modules and the imports forming it
+        #[arg(long)]
+        shortest: bool,
+    },
+    /// Print import chains breaking layering contracts, exits with 1 when there are any
+    Contracts {
+        #[command(flatten)]
+        graph: GraphArgs,
+        /// TOML file with a `[[contracts]]` table per contract
+        #[arg(long, value_name = "FILE")]
+        contracts: PathBuf,
+    },
+    /// Compare two graphs written by `export --format json`, exits with 1 when they differ
+    Diff {
+        /// Graph before the changes (ex: of the base branch)
+        base: PathBuf,
+        /// Graph after the changes (ex: of the head branch)
+        head: PathBuf,
+        #[arg(long)]
+        json: bool,
+        /// Indent JSON
+        #[arg(long)]
+        pretty: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Dot,
+    Mermaid,
+}
+
+#[derive(Args)]
+struct GraphArgs {
+    /// Module names or paths to source files, each of them is a root
+    #[arg(required_unless_present_any = ["project", "graph"])]
+    entries: Vec<String>,
+    /// Build every module under a project directory instead
+    #[arg(long, conflicts_with_all = ["entries", "graph"])]
+    project: Option<PathBuf>,
+    /// Load a graph written by `export --format json` instead of building one
+    #[arg(long, conflicts_with = "entries")]
+    graph: Option<PathBuf>,
+    /// Interpreter whose import configuration (`sys.path`, built-in modules, ...) is used
+    #[arg(long, default_value = "python3")]
+    python: String,
+    /// Only search `--path` entries, without running an interpreter
+    #[arg(long)]
+    no_python: bool,
+    /// Search path entry searched before those of the interpreter
+    #[arg(long = "path", value_name = "DIR")]
+    paths: Vec<String>,
+    /// Only follow imports executed when a module is imported
+    #[arg(long)]
+    eager_only: bool,
+    /// Drop imports under `if TYPE_CHECKING:`
+    #[arg(long)]
+    exclude_type_checking: bool,
+    /// Follow `importlib.import_module("...")` and similar calls with a literal module name
+    #[arg(long)]
+    follow_dynamic: bool,
+    /// Modules never added to the graph, a prefix (`numpy`) or a glob (`*.tests`)
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+    /// Modules added without following their imports
+    #[arg(long, value_name = "PATTERN")]
+    stop_at: Vec<String>,
+    /// Modules this far from the roots are added without following their imports
+    #[arg(long)]
+    max_depth: Option<i32>,
+    /// Directory where parsed imports are kept between runs
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+    /// Read and parse modules on several threads
+    #[arg(long)]
+    parallel: bool,
+}
+
+fn load_json(path: &Path) -> Result<DepGraph, FastDepError> {
+    let data = fs::read_to_string(path).map_err(|err| FastDepError::io(&path.to_string_lossy(), err))?;
+    DepGraph::from_json(&data)
+}
+
+impl GraphArgs {
+    fn load(&self) -> Result<DepGraph, FastDepError> {
+        if let Some(path) = &self.graph {
+            return load_json(path)
+        }
+
+        let (mut resolver, classifier) = if self.no_python {
+            let resolver = Resolver::new(vec![]);
+            let classifier = Classifier::for_resolver(&resolver);
+            (resolver, classifier)
+        } else {
+            let config = InterpreterConfig::query(&self.python)?;
+            (config.resolver(), config.classifier())
+        };
+        resolver.search_paths.splice(0..0, self.paths.iter().cloned());
+
+        let patterns = |patterns: &[String]| patterns.iter().map(|pattern| ModulePattern::parse(pattern)).collect();
+        let options = BuildOptions {
+            eager_only: self.eager_only,
+            exclude_type_checking: self.exclude_type_checking,
+            follow_dynamic: self.follow_dynamic,
+            parallel: self.parallel,
+            threads: 0,
+            exclude: patterns(&self.exclude),
+            stop_at: patterns(&self.stop_at),
+            max_depth: self.max_depth,
+        };
+
+        let mut builder = GraphBuilder::with_resolver(resolver, false).with_options(options).with_classifier(classifier);
+        if let Some(cache_dir) = &self.cache_dir {
+            builder = builder.with_cache_dir(cache_dir)?;
+        }
+
+        match &self.project {
+            Some(project) => builder.build_project(project),
+            None => {
+                let entries: Vec<BuildEntry> = self.entries.iter().map(|entry| BuildEntry::parse(entry)).collect();
+                builder.build_many(&entries)
+            }
+        }
+    }
+}
+
+// Output may be piped into a command which exits early (ex: `head`)
+fn print(text: &str) {
+    let _ = io::stdout().lock().write_all(text.as_bytes());
+}
+
+fn summary(graph: &DepGraph) -> String {
+    let mut out = format!(
+        "{} modules, {} dependencies from {}\n",
+        graph.size(),
+        graph.num_dependencies(),
+        graph.roots.join(", ")
+    );
+
+    for category in ModuleCategory::ALL {
+        let modules = graph.by_category(category).len();
+        if modules > 0 {
+            out.push_str(&format!("  {}: {}\n", category.as_str(), modules));
+        }
+    }
+    if !graph.errors.is_empty() {
+        out.push_str(&format!("{} errors:\n", graph.errors.len()));
+        for (module, err) in &graph.errors {
+            out.push_str(&format!("  {}: {}\n", module, err));
+        }
+    }
+    if !graph.unresolved.is_empty() {
+        out.push_str(&format!("{} unresolved imports:\n", graph.unresolved.len()));
+        for unresolved in &graph.unresolved {
+            out.push_str(&format!("  {}: {}\n", unresolved.importer, unresolved.text));
+        }
+    }
+
+    out
+}
+
+// `a -> b -> c` followed by the statement behind each step
+fn chain(path: &[String], edges: &[ImportEdge]) -> String {
+    let mut out = format!("{}\n", path.join(" -> "));
+    for (i, edge) in edges.iter().enumerate() {
+        let file = edge.file.as_deref().unwrap_or(&path[i]);
+        out.push_str(&format!("    {}:{} imports {}\n", file, edge.line, path[i + 1]));
+    }
+    out
+}
+
+fn run(command: Command) -> Result<ExitCode, FastDepError> {
+    match command {
+        Command::Build { graph } => {
+            print(&summary(&graph.load()?));
+        },
+        Command::Tree { graph, depth } => {
+            print(&graph.load()?.to_tree(depth));
+        },
+        Command::Export { graph, format, output, pretty, depth, collapse_packages, color } => {
+            let graph = graph.load()?;
+            let options = ExportOptions {
+                max_depth: depth,
+                collapse_packages: collapse_packages,
+                color: color,
+            };
+            let data = match format {
+                Format::Json => graph.to_json(pretty)? + "\n",
+                Format::Dot => graph.to_dot(&options),
+                Format::Mermaid => graph.to_mermaid(&options),
+            };
+
+            match output {
+                Some(path) => fs::write(&path, data).map_err(|err| FastDepError::io(&path.to_string_lossy(), err))?,
+                None => print(&data)
+            }
+        },
+        Command::Path { graph, from, to, all, limit } => {
+            let graph = graph.load()?;
+            let paths = if all {
+                graph.all_paths(&from, &to, limit)?
+            } else {
+                graph.path(&from, &to)?.into_iter().collect()
+            };
+
+            if paths.is_empty() {
+                eprintln!("No import chain from '{}' to '{}'", from, to);
+                return Ok(ExitCode::from(1))
+            }
+            for path in paths {
+                print(&format!("{}\n", path.join(" -> ")));
+            }
+        },
+        Command::Cycles { graph, shortest } => {
+            let cycles = graph.load()?.cycles(shortest);
+            for cycle in &cycles {
+                match (&cycle.path, &cycle.edges) {
+                    (Some(path), Some(edges)) => print(&chain(path, edges)),
+                    _ => print(&format!("{}\n", cycle.modules.join(", ")))
+                }
+            }
+
+            if !cycles.is_empty() {
+                return Ok(ExitCode::from(1))
+            }
+        },
+        Command::Contracts { graph, contracts } => {
+            let contracts = load_contracts(&contracts)?;
+            let violations = graph.load()?.check_contracts(&contracts);
+            for violation in &violations {
+                print(&format!("{}: {}", violation.contract, chain(&violation.path, &violation.edges)));
+            }
+
+            if !violations.is_empty() {
+                return Ok(ExitCode::from(1))
+            }
+        },
+        Command::Diff { base, head, json, pretty } => {
+            let diff = load_json(&base)?.diff(&load_json(&head)?);
+            if json {
+                print(&(diff.to_json(pretty)? + "\n"));
+            } else {
+                print(&diff.to_text());
+            }
+
+            if !diff.is_empty() {
+                return Ok(ExitCode::from(1))
+            }
+        },
+    }
+
+    return Ok(ExitCode::SUCCESS)
+}
+
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{}] {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let level = match cli.verbose {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        _ => LevelFilter::Debug,
+    };
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+
+    match run(cli.command) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::from(2)
+        }
+    }
+}
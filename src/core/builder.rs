@@ -1,16 +1,24 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::fs::File;
 use std::io::prelude::*;
 use log::{debug, info, warn};
 
 use pyo3::prelude::*;
 
+use crate::error::FastDepError;
 use crate::importlib::*;
 use crate::minimal_parser::*;
+use super::cache::DiskCache;
+use super::classify::Classifier;
+use super::interpreter::InterpreterConfig;
+use super::prefetch::{PrefetchedImports, prefetch};
+use super::patterns::{ModulePattern, any_matches};
+use super::project::scan_project;
 use super::types::*;
 
+#[derive(Default)]
 pub struct BuildMetadata {
     pub processed: usize,
     pub from_cache: usize,
@@ -25,37 +33,195 @@ impl BuildMetadata {
     }
 }
 
+/// Entry point of a build, a module name or a path to a source file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildEntry {
+    Module(String),
+    File(PathBuf),
+}
+
+impl BuildEntry {
+    // Paths are told apart from module names by a path separator or a `.py` suffix
+    pub fn parse(entry: &str) -> BuildEntry {
+        if entry.ends_with(".py") || entry.contains('/') || entry.contains(std::path::MAIN_SEPARATOR) {
+            BuildEntry::File(PathBuf::from(entry))
+        } else {
+            BuildEntry::Module(entry.to_string())
+        }
+    }
+}
+
+// Fixed for the lifetime of a builder, the cache is only valid for a single set of options
+#[derive(Clone, Default)]
+pub struct BuildOptions {
+    // Only follow imports executed when a module is imported (not those inside functions)
+    pub eager_only: bool,
+    // Drop imports only seen by type checkers (under `if TYPE_CHECKING:`), otherwise they are kept and labelled
+    pub exclude_type_checking: bool,
+    // Follow calls importing a module named by a string literal (ex: `importlib.import_module("pkg.mod")`)
+    pub follow_dynamic: bool,
+    // Read and parse modules on a thread pool, the resulting graph is the same as a serial build
+    pub parallel: bool,
+    // Worker threads for parallel builds, 0 for one per CPU
+    pub threads: usize,
+    // Modules never added to the graph, imports of them are dropped
+    pub exclude: Vec<ModulePattern>,
+    // Modules added to the graph without following their imports
+    pub stop_at: Vec<ModulePattern>,
+    // Modules at this depth are added without following their imports
+    pub max_depth: Option<i32>,
+}
+
+impl BuildOptions {
+    pub fn is_excluded(&self, name: &str) -> bool {
+        any_matches(&self.exclude, name)
+    }
+
+    // Whether imports of a module found at `depth` are followed, unknown depths are
+    pub fn descends(&self, name: &str, depth: Option<i32>) -> bool {
+        !any_matches(&self.stop_at, name) && !self.beyond_max_depth(depth)
+    }
+
+    pub fn beyond_max_depth(&self, depth: Option<i32>) -> bool {
+        matches!((self.max_depth, depth), (Some(max_depth), Some(depth)) if depth >= max_depth)
+    }
+}
+
 #[pyclass]
 pub struct GraphBuilder {
     pub graph: DepGraph,
     processing: HashSet<String>,
     verbose: bool,
     cache: Option<DepGraph>,
-    metadata: BuildMetadata
+    metadata: BuildMetadata,
+    resolver: Resolver,
+    classifier: Classifier,
+    options: BuildOptions,
+    disk_cache: Option<DiskCache>,
+    // Imports parsed ahead of the build by origin, only used for parallel builds
+    prefetched: HashMap<String, PrefetchedImports>,
+    // Modules not followed because of `max_depth` which may turn out to be closer to a root
+    truncated: HashSet<String>,
+    // Literal `__all__` of the modules loaded so far, by name
+    dunder_all: HashMap<String, Vec<String>>,
 }
 
 #[pymethods]
 impl GraphBuilder {
     #[new]
-    pub fn new(verbose: Option<bool>) -> Self {
-        let verbose = if let Some(verbose) = verbose {
-            verbose
-        } else {
-            false
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        py: Python<'_>,
+        verbose: Option<bool>,
+        eager_only: Option<bool>,
+        exclude_type_checking: Option<bool>,
+        follow_dynamic: Option<bool>,
+        cache_dir: Option<String>,
+        parallel: Option<bool>,
+        threads: Option<usize>,
+        exclude: Option<Vec<String>>,
+        stop_at: Option<Vec<String>>,
+        max_depth: Option<i32>,
+        project_roots: Option<Vec<String>>
+    ) -> PyResult<Self> {
+        let patterns = |patterns: Option<Vec<String>>| {
+            patterns.unwrap_or_default().iter().map(|pattern| ModulePattern::parse(pattern)).collect()
+        };
+        let verbose = verbose.unwrap_or(false);
+        let options = BuildOptions {
+            eager_only: eager_only.unwrap_or(false),
+            exclude_type_checking: exclude_type_checking.unwrap_or(false),
+            follow_dynamic: follow_dynamic.unwrap_or(false),
+            parallel: parallel.unwrap_or(false),
+            threads: threads.unwrap_or(0),
+            exclude: patterns(exclude),
+            stop_at: patterns(stop_at),
+            max_depth: max_depth,
         };
 
-        let builder = GraphBuilder {
+        // Snapshot the interpreter's import configuration once, all lookups after this are native
+        let config = InterpreterConfig::from_python(py)?;
+        let resolver = config.resolver();
+        let mut classifier = config.classifier();
+        for root in project_roots.unwrap_or_default() {
+            classifier.add_project_root(Path::new(&root));
+        }
+
+        let builder = GraphBuilder::with_resolver(resolver, verbose).with_options(options).with_classifier(classifier);
+        match cache_dir {
+            Some(cache_dir) => Ok(builder.with_cache_dir(Path::new(&cache_dir))?),
+            None => Ok(builder)
+        }
+    }
+
+    pub fn build(&mut self, py: Python<'_>, source: &str, package: Option<String>) -> PyResult<DepGraph> {
+        // Nothing below needs the interpreter, worker threads of parallel builds may still log through it
+        Ok(py.allow_threads(|| self.build_source(source, package))?)
+    }
+
+    #[pyo3(name = "build_module")]
+    pub fn py_build_module(&mut self, py: Python<'_>, name: &str) -> PyResult<DepGraph> {
+        Ok(py.allow_threads(|| self.build_module(name))?)
+    }
+
+    #[pyo3(name = "build_many")]
+    pub fn py_build_many(&mut self, py: Python<'_>, entries: Vec<String>) -> PyResult<DepGraph> {
+        let entries: Vec<BuildEntry> = entries.iter().map(|entry| BuildEntry::parse(entry)).collect();
+        Ok(py.allow_threads(|| self.build_many(&entries))?)
+    }
+
+    #[pyo3(name = "build_file")]
+    pub fn py_build_file(&mut self, py: Python<'_>, path: &str) -> PyResult<DepGraph> {
+        Ok(py.allow_threads(|| self.build_file(Path::new(path)))?)
+    }
+
+    #[pyo3(name = "build_project")]
+    pub fn py_build_project(&mut self, py: Python<'_>, path: &str) -> PyResult<DepGraph> {
+        Ok(py.allow_threads(|| self.build_project(Path::new(path)))?)
+    }
+}
+
+impl GraphBuilder {
+    pub fn with_resolver(resolver: Resolver, verbose: bool) -> Self {
+        GraphBuilder {
             graph: DepGraph::new(),
             processing: HashSet::new(),
             verbose: verbose,
             cache: None,
             metadata: BuildMetadata::new(),
-        };
+            classifier: Classifier::for_resolver(&resolver),
+            resolver: resolver,
+            options: BuildOptions::default(),
+            disk_cache: None,
+            prefetched: HashMap::new(),
+            truncated: HashSet::new(),
+            dunder_all: HashMap::new(),
+        }
+    }
+
+    pub fn with_options(mut self, options: BuildOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_classifier(mut self, classifier: Classifier) -> Self {
+        self.classifier = classifier;
+        self
+    }
 
-        builder
+    // Persist parsed imports and resolved specs in `dir`, saved after every build
+    pub fn with_cache_dir(mut self, dir: &Path) -> Result<Self, FastDepError> {
+        let mut disk_cache = DiskCache::load(dir, &self.resolver)?;
+        disk_cache.seed(&mut self.resolver);
+        self.disk_cache = Some(disk_cache);
+        Ok(self)
     }
 
-    pub fn build(&mut self, source: &str, package: Option<String>) -> DepGraph {
+    pub fn disk_cache(&self) -> Option<&DiskCache> {
+        self.disk_cache.as_ref()
+    }
+
+    pub fn build_source(&mut self, source: &str, package: Option<String>) -> Result<DepGraph, FastDepError> {
         // Trying to make source look like a package
         let (package, dirs) = if let Some(package) = package {
             (package, Some(vec![]))
@@ -75,8 +241,251 @@ impl GraphBuilder {
 
 
         let node = DepNode::new(spec.clone(), Some(0));
-        self.graph.add(node);
-        self._process_imports(spec, source);
+        let stmts = self._parse(&spec, source).map(|parsed| Some(parsed.imports));
+        self._build(node, stmts)
+    }
+
+    /// Build the graph of an importable module, the root node is named after it.
+    pub fn build_module(&mut self, name: &str) -> Result<DepGraph, FastDepError> {
+        let spec = self._entry_spec(&BuildEntry::Module(name.to_string()))?;
+
+        let node = DepNode::new(spec, Some(0));
+        let stmts = self._load_imports(&node);
+        self._build(node, stmts)
+    }
+
+    /// Build the graph of a source file, named after the module it is importable as or `__main__` for scripts.
+    pub fn build_file(&mut self, path: &Path) -> Result<DepGraph, FastDepError> {
+        let spec = self._entry_spec(&BuildEntry::File(path.to_path_buf()))?;
+
+        let node = DepNode::new(spec, Some(0));
+        let stmts = self._load_imports(&node);
+        self._build(node, stmts)
+    }
+
+    // First search path entry under which `path` is found by the name derived from its location
+    fn _module_for_file(&mut self, path: &Path) -> Option<ModuleSpec> {
+        for entry in self.resolver.search_paths.clone() {
+            let entry = if entry.is_empty() {
+                // Skipped like the resolver does when the working directory can't be read
+                match std::env::current_dir() {
+                    Ok(dir) => dir,
+                    Err(_) => continue
+                }
+            } else {
+                PathBuf::from(entry)
+            };
+            let Ok(entry) = entry.canonicalize() else {
+                continue
+            };
+            let Ok(relative) = path.strip_prefix(&entry) else {
+                continue
+            };
+
+            let mut parts: Vec<String> = relative.iter().map(|part| part.to_string_lossy().to_string()).collect();
+            // `path` is the entry itself
+            let Some(file) = parts.pop() else {
+                continue
+            };
+            let stem = file.split('.').next().unwrap().to_string();
+            if stem != "__init__" {
+                parts.push(stem);
+            }
+            // Directories which are not identifiers can not be packages
+            let identifier = |part: &String| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if parts.is_empty() || !parts.iter().all(identifier) {
+                continue;
+            }
+
+            let name = parts.join(".");
+            if let Some(spec) = self.resolver.find_spec(&name) {
+                let origin = spec.origin.as_ref().and_then(|origin| Path::new(origin).canonicalize().ok());
+                if origin.as_deref() == Some(path) {
+                    return Some(spec)
+                }
+            }
+        }
+
+        return None
+    }
+
+    /// Build one graph from several entry points, each of them is a root at depth 0.
+    ///
+    /// Depths are the shortest distance from any of the roots, see `DepGraph::depths_from` for a single one.
+    /// Scripts are named after their path so several of them can be part of the same graph.
+    pub fn build_many(&mut self, entries: &[BuildEntry]) -> Result<DepGraph, FastDepError> {
+        let mut roots: Vec<String> = vec![];
+        let mut result = Ok(());
+        let pool = self._pool();
+
+        for entry in entries {
+            let mut spec = match self._entry_spec(entry) {
+                Ok(spec) => spec,
+                Err(err) => {
+                    result = Err(err);
+                    break
+                }
+            };
+            if spec.name == "__main__" {
+                spec.name = spec.origin.clone().unwrap();
+            }
+
+            let name = spec.name.clone();
+            if !roots.contains(&name) {
+                roots.push(name.clone());
+            }
+            // Reached from an earlier entry, its imports have been followed already
+            if self.graph.has_node(&name) {
+                continue;
+            }
+
+            let node = DepNode::new(spec, Some(0));
+            let stmts = self._load_imports(&node);
+            if let Err(err) = self._process_root(node, stmts, pool.as_ref()) {
+                result = Err(err);
+                break
+            }
+        }
+
+        if result.is_ok() {
+            result = self._expand_truncated(&roots);
+            self.graph.recompute_depths(&roots);
+        }
+        self._finish(roots, result)
+    }
+
+    /// Build the graph of every module under a project directory, see `scan_project` for how they are named.
+    ///
+    /// All of them are roots so modules nothing imports are part of the graph, see `DepGraph::orphans`.
+    /// Project modules resolve to the scanned files even when the project is not on the search path.
+    pub fn build_project(&mut self, root: &Path) -> Result<DepGraph, FastDepError> {
+        let mut specs = scan_project(root)?;
+        // Modules of the project are first-party, those of other directories third-party
+        self.classifier.add_project_root(root);
+        specs.retain(|spec| !self.options.is_excluded(&spec.name));
+        info!("Found {} modules in project: {}", specs.len(), root.display());
+
+        // Kept for later builds, the project shadows installed copies of itself
+        for spec in &specs {
+            self.resolver.override_spec(&spec.name.clone(), spec.clone());
+        }
+
+        let roots: Vec<String> = specs.iter().map(|spec| spec.name.clone()).collect();
+        let mut result = Ok(());
+        let pool = self._pool();
+        for spec in specs {
+            // Reached from an earlier module, its imports have been followed already
+            if self.graph.has_node(&spec.name) {
+                continue;
+            }
+
+            let name = spec.name.clone();
+            let node = DepNode::new(spec, Some(0));
+            let stmts = self._load_imports(&node);
+            // A broken file should not hide the rest of the project
+            match self._process_root(node, stmts, pool.as_ref()) {
+                Err(err @ FastDepError::Graph { .. }) => {
+                    result = Err(err);
+                    break
+                },
+                Err(err) => self.graph.add_error(&name, err),
+                Ok(()) => ()
+            }
+        }
+
+        if result.is_ok() {
+            result = self._expand_truncated(&roots);
+            self.graph.recompute_depths(&roots);
+        }
+        self._finish(roots, result)
+    }
+
+    fn _entry_spec(&mut self, entry: &BuildEntry) -> Result<ModuleSpec, FastDepError> {
+        match entry {
+            BuildEntry::Module(name) => self.resolver.find_spec(name).ok_or_else(|| FastDepError::Resolution {
+                name: name.to_string(),
+                message: format!("No module named '{}'", name)
+            }),
+            BuildEntry::File(path) => {
+                let path = path.canonicalize().map_err(|err| FastDepError::io(&path.to_string_lossy(), err))?;
+                match self._module_for_file(&path) {
+                    Some(spec) => Ok(spec),
+                    // Same as `python path/to/script.py`, relative imports fail without a parent package
+                    None => Ok(ModuleSpec::new("__main__", Some(path.to_string_lossy().to_string()), None))
+                }
+            }
+        }
+    }
+
+    fn _build(&mut self, node: DepNode, stmts: Result<Option<Vec<ImportStmt>>, FastDepError>) -> Result<DepGraph, FastDepError> {
+        let roots = vec![node.name.clone()];
+        let pool = self._pool();
+        let result = self._process_root(node, stmts, pool.as_ref()).and_then(|_| self._expand_truncated(&roots));
+        self._finish(roots, result)
+    }
+
+    // The traversal is depth first, a module cut at `max_depth` may be closer to a root through a chain found later.
+    // Follows those until every module within `max_depth` of a root has been followed, depths are recomputed.
+    fn _expand_truncated(&mut self, roots: &[String]) -> Result<(), FastDepError> {
+        while !self.truncated.is_empty() {
+            self.graph.recompute_depths(roots);
+
+            let mut expand: Vec<String> = self.truncated.iter()
+                .filter(|name| {
+                    let depth = self.graph.nodes.get(*name).and_then(|node| node.borrow().depth);
+                    !self.options.beyond_max_depth(depth)
+                })
+                .cloned()
+                .collect();
+            if expand.is_empty() {
+                break
+            }
+            expand.sort();
+
+            for name in expand {
+                self.truncated.remove(&name);
+                let node = self.graph.nodes.get(&name).unwrap().borrow().clone();
+                let result = match self._load_imports(&node) {
+                    Ok(Some(stmts)) => self._process_imports(node.spec, stmts),
+                    Ok(None) => Ok(()),
+                    Err(err) => Err(err)
+                };
+                match result {
+                    Err(err @ FastDepError::Graph { .. }) => return Err(err),
+                    Err(err) => self.graph.add_error(&name, err),
+                    Ok(()) => ()
+                }
+            }
+        }
+
+        return Ok(())
+    }
+
+    // Adds `node` at depth 0 and follows its imports, prefetching them on `pool` for parallel builds
+    fn _process_root(
+        &mut self,
+        node: DepNode,
+        stmts: Result<Option<Vec<ImportStmt>>, FastDepError>,
+        pool: Option<&rayon::ThreadPool>
+    ) -> Result<(), FastDepError> {
+        let spec = node.spec.clone();
+        self.graph.add(node)?;
+        if self.options.beyond_max_depth(Some(0)) {
+            return Ok(())
+        }
+        match stmts {
+            Ok(Some(stmts)) => {
+                if let Some(pool) = pool {
+                    self._prefetch(&spec, &stmts, pool);
+                }
+                self._process_imports(spec, stmts)
+            },
+            Ok(None) => Ok(()),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn _finish(&mut self, roots: Vec<String>, result: Result<(), FastDepError>) -> Result<DepGraph, FastDepError> {
 
         if self.metadata.from_cache == 0 {
             info!(
@@ -91,133 +500,322 @@ impl GraphBuilder {
             );
         }
 
+        if let Some(disk_cache) = self.disk_cache.as_mut() {
+            info!("Loaded imports of {} modules from disk ({} parsed).", disk_cache.hits, disk_cache.misses);
+            disk_cache.update_specs(&self.resolver);
+            if let Err(err) = disk_cache.save() {
+                warn!("Unable to save cache: {}", err);
+            }
+        }
+
         // Reset for next build
-        let _ = mem::replace(&mut self.processing, HashSet::new());
-        let _ = mem::replace(&mut self.metadata, BuildMetadata::new());
-        let graph = mem::replace(&mut self.graph, DepGraph::new());
+        self.processing.clear();
+        self.prefetched.clear();
+        self.truncated.clear();
+        self.dunder_all.clear();
+        self.metadata = BuildMetadata::new();
+        let mut graph = mem::take(&mut self.graph);
+        graph.roots = roots;
+        graph.classify(&self.classifier);
+        graph.attach_distributions(&mut self.resolver);
+
+        // Failures in the source itself (or broken graph invariants) fail the whole build
+        result?;
 
         // Cache all nodes
         let to_cache = graph.clone();
         if let Some(cache) = self.cache.as_mut() {
-            cache.merge(to_cache)
+            cache.merge(to_cache)?
         } else {
             self.cache = Some(to_cache)
         }
 
-        return graph
+        return Ok(graph)
     }
-}
 
-impl GraphBuilder {
-    pub fn _load_source(&mut self, node: &DepNode) -> Option<String> {
-        if let Some(path_str) = &node.spec.origin {
-            // Some origins we would be able to parse
-            if path_str == "built-in"  || path_str == "frozen" {
-                return None
+    pub fn _load_source(&mut self, node: &DepNode) -> Result<Option<String>, FastDepError> {
+        load_source(&node.spec)
+    }
+
+    fn _parse(&self, spec: &ModuleSpec, source: &str) -> Result<ParsedSource, FastDepError> {
+        parse_module(spec, source)
+    }
+
+    // Names which are not submodules are skipped by `_process_dependency`, errors are reported when the package itself
+    // is loaded
+    fn _dunder_all(&mut self, spec: &ModuleSpec) -> Vec<String> {
+        // Recorded when the package was loaded, unless its imports were not followed or came from the in-memory cache
+        if let Some(names) = self.dunder_all.get(&spec.name) {
+            return names.clone()
+        }
+
+        let names = match self._load_parsed(&DepNode::new(spec.clone(), None)) {
+            Ok(parsed) => parsed.and_then(|parsed| parsed.dunder_all).unwrap_or_default(),
+            Err(err) => {
+                debug!("Unable to read `__all__` of '{}': {}", spec.name, err);
+                vec![]
             }
+        };
+        self.dunder_all.insert(spec.name.clone(), names.clone());
 
-            let source_path = Path::new(path_str);
-            // Only load python files
-            if let Some(ext) = source_path.extension() {
-                if ext != "py" {
-                    return None
-                }
-            } else {
-                warn!(
-                    "Unable to load extension for spec '{}' with origin '{}' skipping.",
-                    node.spec.name,
-                    path_str
-                );
-                return None
+        names
+    }
+
+    // Worker threads shared by every root of a parallel build, `None` for serial builds
+    fn _pool(&self) -> Option<rayon::ThreadPool> {
+        if !self.options.parallel {
+            return None
+        }
+
+        match rayon::ThreadPoolBuilder::new().num_threads(self.options.threads).build() {
+            Ok(pool) => Some(pool),
+            Err(err) => {
+                warn!("Unable to start worker threads, building serially: {}", err);
+                None
+            }
+        }
+    }
+
+    fn _prefetch(&mut self, spec: &ModuleSpec, stmts: &[ImportStmt], pool: &rayon::ThreadPool) {
+        // Modules of earlier roots are in the graph already or waiting in `prefetched`
+        let mut known: HashSet<String> = self.prefetched.keys().cloned().collect();
+        for node_cell in self.graph.nodes.values() {
+            if let Some(origin) = &node_cell.borrow().spec.origin {
+                known.insert(origin.clone());
+            }
+        }
+
+        let prefetched = prefetch(
+            &mut self.resolver,
+            &self.options,
+            self.disk_cache.as_mut(),
+            spec,
+            stmts,
+            &known,
+            pool
+        );
+        info!("Prefetched imports of {} modules.", prefetched.len());
+        // Several roots may be prefetched for the same build
+        self.prefetched.extend(prefetched);
+    }
+
+    // Imports of a module, its `__all__` is kept for star imports of it
+    fn _load_imports(&mut self, node: &DepNode) -> Result<Option<Vec<ImportStmt>>, FastDepError> {
+        let Some(parsed) = self._load_parsed(node)? else {
+            return Ok(None)
+        };
+        self.dunder_all.insert(node.spec.name.clone(), parsed.dunder_all.unwrap_or_default());
+
+        return Ok(Some(parsed.imports))
+    }
+
+    // Parsed source of a module, from the disk cache when one is configured and the file is unchanged
+    fn _load_parsed(&mut self, node: &DepNode) -> Result<Option<ParsedSource>, FastDepError> {
+        let origin = node.spec.origin.clone();
+        if let Some(prefetched) = origin.as_ref().and_then(|origin| self.prefetched.remove(origin)) {
+            if let (Some(disk_cache), Some(source)) = (self.disk_cache.as_mut(), &prefetched.source) {
+                disk_cache.insert_imports(origin.as_ref().unwrap(), source, prefetched.parsed.clone());
             }
+            return Ok(Some(prefetched.parsed))
+        }
+        if let (Some(disk_cache), Some(origin)) = (self.disk_cache.as_mut(), &origin) {
+            if let Some(parsed) = disk_cache.imports(origin) {
+                return Ok(Some(parsed))
+            }
+        }
 
-            debug!("Loading file: {}", path_str);
-            let mut source_file = File::open(Path::new(source_path));
-            if let Err(err) = source_file {
-                warn!(
-                    "Unable to load file for spec '{}' with origin '{}' skipping.",
-                    node.spec.name,
-                    path_str,
-                );
-                eprintln!("System error: {err}");
-                return None
+        let Some(source) = self._load_source(node)? else {
+            return Ok(None)
+        };
+        if let (Some(disk_cache), Some(origin)) = (self.disk_cache.as_mut(), &origin) {
+            if let Some(parsed) = disk_cache.imports_for_source(origin, &source) {
+                return Ok(Some(parsed))
             }
-            let mut source = String::new();
+        }
 
-            source_file.unwrap().read_to_string(&mut source).unwrap();
-            return Some(source)
+        let parsed = self._parse(&node.spec, &source)?;
+        if let (Some(disk_cache), Some(origin)) = (self.disk_cache.as_mut(), &origin) {
+            disk_cache.insert_imports(origin, &source, parsed.clone());
         }
 
-        return None
+        return Ok(Some(parsed))
     }
 
-    pub fn _process_imports(&mut self, spec: ModuleSpec, source: &str) {
+    pub fn _process_imports(&mut self, spec: ModuleSpec, stmts: Vec<ImportStmt>) -> Result<(), FastDepError> {
         debug!(">>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>");
         debug!("Expanding '{}'", spec.name);
         debug!(">>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>>");
 
-        // Circular check
-        assert!(!self.processing.contains(&spec.name), "Double processing detected for name: '{}'", spec.name);
-        self.processing.insert(spec.name.clone());
-
-        let stmts = parse(source);
         if self.verbose {
             dump_imports(&stmts);
         }
 
+        // Circular check
+        if self.processing.contains(&spec.name) {
+            return Err(FastDepError::graph(format!("Double processing detected for name: '{}'", spec.name)))
+        }
+        self.processing.insert(spec.name.clone());
+
         for stmt in stmts {
             match stmt {
-                ImportStmt::Import { names } => {
+                ImportStmt::Dynamic { name, .. } if !self.options.follow_dynamic => {
+                    debug!("Skipping dynamic import of '{}' in '{}'", name, spec.name);
+                },
+                ImportStmt::Import { context, .. } | ImportStmt::ImportFrom { context, .. } | ImportStmt::Dynamic { context, .. }
+                    if self.options.eager_only && !context.is_eager() => {
+                    debug!("Skipping lazy import in '{}'", spec.name);
+                },
+                ImportStmt::Import { context, .. } | ImportStmt::ImportFrom { context, .. } | ImportStmt::Dynamic { context, .. }
+                    if self.options.exclude_type_checking && context.type_checking => {
+                    debug!("Skipping type checking import in '{}'", spec.name);
+                },
+                ImportStmt::Import { names, context, .. } => {
                     for alias in names {
-                        // Don't care about asname, we only need the import name to analyze dependencies
-                        self._process_dependency(Some(&spec.name), &alias.name);
+                        let edge = ImportEdge {
+                            file: spec.origin.clone(),
+                            line: alias.line,
+                            column: alias.column,
+                            kind: ImportKind::Import,
+                            asname: alias.asname.clone(),
+                            level: 0,
+                            scope: context.scope,
+                            conditional: context.conditional,
+                            type_checking: context.type_checking
+                        };
+                        self._process_import(&spec.name, &alias.name, alias.name.clone(), &edge)?;
                     }
                 },
-                ImportStmt::ImportFrom { module, names, level } => {
+                ImportStmt::ImportFrom { module, names, level, line, column, context } => {
                     if let (Some(module), Some(level)) = (module, level) {
+                        let text = format!("{}{}", ".".repeat(level), module);
                         let module_name = if level != 0 {
-                            if spec.parent == "<terminal>" {
-                                panic!("Attempted relative import from terminal node (no known parent package)");
-                            }
-
                             // Resolve name relative to the current package (parent in ModuleSpec)
-                            resolve_name (
-                                &module,
-                                &spec.parent,
-                                &level
-                            )
+                            let resolved = if spec.parent == "<terminal>" {
+                                Err(FastDepError::Resolution {
+                                    name: format!("{}{}", ".".repeat(level), module),
+                                    message: "Attempted relative import from terminal node (no known parent package)".to_string()
+                                })
+                            } else {
+                                resolve_name (
+                                    &module,
+                                    &spec.parent,
+                                    &level
+                                )
+                            };
+
+                            // Only this statement is broken, keep going with the rest of the module
+                            match resolved {
+                                Ok(module_name) => module_name,
+                                Err(err) => {
+                                    self.graph.add_error(&spec.name, err);
+                                    continue;
+                                }
+                            }
                         } else {
                             module
                         };
 
                         // Place dependency on the module
-                        self._process_dependency(
-                            Some(&spec.name),
-                            &module_name
-                        );
+                        let edge = ImportEdge {
+                            file: spec.origin.clone(),
+                            line: line,
+                            column: column,
+                            kind: ImportKind::ImportFrom,
+                            asname: None,
+                            level: level,
+                            scope: context.scope,
+                            conditional: context.conditional,
+                            type_checking: context.type_checking
+                        };
+                        self._process_import(
+                            &spec.name,
+                            &module_name,
+                            text,
+                            &edge
+                        )?;
 
                         // If this is a package we need to process the names b/c they may be submodules
-                        let module_spec: Option<ModuleSpec> = find_spec(
+                        let module_spec: Option<ModuleSpec> = self.resolver.find_spec(
                             &module_name
                         );
 
                         if let Some(s) = module_spec {
                             if s.is_package() {
                                 for alias in names {
-                                    self._process_dependency(
-                                        Some(&spec.name),
-                                        &format!(
-                                            "{}.{}",
-                                            module_name,
-                                            alias.name
-                                        )
-                                    )
+                                    let edge = ImportEdge {
+                                        file: spec.origin.clone(),
+                                        line: alias.line,
+                                        column: alias.column,
+                                        kind: ImportKind::ImportFrom,
+                                        asname: alias.asname.clone(),
+                                        level: level,
+                                        scope: context.scope,
+                                        conditional: context.conditional,
+                                        type_checking: context.type_checking
+                                    };
+                                    // `from pkg import *` imports the submodules named in the package's `__all__`
+                                    let submodules = if alias.is_star() {
+                                        self._dunder_all(&s)
+                                    } else {
+                                        vec![alias.name.clone()]
+                                    };
+                                    for submodule in submodules {
+                                        self._process_dependency(
+                                            Some(&spec.name),
+                                            &format!(
+                                                "{}.{}",
+                                                module_name,
+                                                submodule
+                                            ),
+                                            &edge
+                                        )?
+                                    }
                                 }
                             }
                         }
                     } else {
-                        panic!("Broken assumption of implementation, revist this block to see if there are issues.")
+                        return Err(FastDepError::graph(
+                            "Broken assumption of implementation, revist this block to see if there are issues.".to_string()
+                        ))
                     }
+                },
+                ImportStmt::Dynamic { name, package, line, column, context, .. } => {
+                    let level = name.len() - name.trim_start_matches('.').len();
+                    let module_name = if level != 0 {
+                        // Relative to the `package` argument, or the current package like `import_module(name, __package__)`
+                        let parent = package.as_deref().unwrap_or(&spec.parent);
+                        let resolved = if parent == "<terminal>" {
+                            Err(FastDepError::Resolution {
+                                name: name.clone(),
+                                message: "Attempted relative import from terminal node (no known parent package)".to_string()
+                            })
+                        } else {
+                            resolve_name(&name[level..], parent, &level)
+                        };
+
+                        match resolved {
+                            Ok(module_name) => module_name,
+                            Err(err) => {
+                                self.graph.add_error(&spec.name, err);
+                                continue;
+                            }
+                        }
+                    } else {
+                        name.clone()
+                    };
+
+                    let edge = ImportEdge {
+                        file: spec.origin.clone(),
+                        line: line,
+                        column: column,
+                        kind: ImportKind::Dynamic,
+                        asname: None,
+                        level: level,
+                        scope: context.scope,
+                        conditional: context.conditional,
+                        type_checking: context.type_checking
+                    };
+                    self._process_import(&spec.name, &module_name, name, &edge)?;
                 }
             }
         }
@@ -225,27 +823,65 @@ impl GraphBuilder {
         debug!("<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<");
         debug!("Done '{}'", spec.name);
         debug!("<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<<");
+
+        return Ok(())
     }
 
-    pub fn _process_dependency(&mut self, from: Option<&String>, name: &str) {
+    // Dependencies named directly by an import statement, unlike parents or the names of a
+    // `from x import y` statement these are expected to exist and are recorded when missing
+    fn _process_import(&mut self, from: &String, name: &str, text: String, edge: &ImportEdge) -> Result<(), FastDepError> {
+        if self.options.is_excluded(name) {
+            debug!("Skipping excluded import: {} -> {}", from, name);
+            // Parents are still imported unless they are excluded too
+            return self._process_parent(Some(from), name, edge)
+        }
+        if self.resolver.find_spec(name).is_some() {
+            return self._process_dependency(Some(from), name, edge)
+        }
+
+        debug!("!!!! Unable to find spec for name: '{}' !!!!", name);
+        // Python would have imported the parents before failing on this name
+        self._process_parent(Some(from), name, edge)?;
+
+        if !self.graph.has_node(name) {
+            self.graph.add(DepNode::missing(name))?;
+        }
+        self.graph.add_dependency(from, name, edge.clone())?;
+        self.graph.add_unresolved(UnresolvedImport {
+            name: name.to_string(),
+            importer: from.clone(),
+            text: text
+        });
+
+        return Ok(())
+    }
+
+    pub fn _process_dependency(&mut self, from: Option<&String>, name: &str, edge: &ImportEdge) -> Result<(), FastDepError> {
         // Maybe expensive but some values will change names after find_spec()
         // TODO: Deal with this in another way?
-        let spec: Option<ModuleSpec> = find_spec(name);
+        let spec: Option<ModuleSpec> = self.resolver.find_spec(name);
 
         if spec.is_none() {
             debug!("!!!! Unable to find spec for name: '{}' !!!!", name);
-            return
+            return Ok(())
         }
 
         // Rebind spec & name to make things easier going forward
         let spec = spec.unwrap();
+        let requested = name;
         let name = spec.name.clone();
 
+        // Checked by both names, aliases are excluded with the module they are bound to
+        if self.options.is_excluded(requested) || self.options.is_excluded(&name) {
+            debug!("Skipping excluded module: {}", name);
+            return self._process_parent(from, &name, edge)
+        }
+
         // Double borrow in add_dependency will fail, self references are valid python, just going to not track them for now.
         // TODO: Example??
         if let Some(from) = from {
             if from == &name {
-                return
+                return Ok(())
             }
         }
 
@@ -260,95 +896,168 @@ impl GraphBuilder {
         if self.graph.has_node(&name) {
             // Just need to update the dependencies if required
             if let Some(from) = from {
-                self.graph.add_dependency(from, &name)
+                self.graph.add_dependency(from, &name, edge.clone())?
             }
 
             // Done!
-            return
+            return Ok(())
         } else if name != "<terminal>" {
             // TODO: Can this happen before reaching out to python
-            if !self.cache.is_none() {
-                if self.cache.as_ref().unwrap().has_node(&name) {
-                    // Process the parent and see if that adds the node first
-                    self._process_parent(from, &name);
-
-                    // Spooky!
-                    // TODO: This logic is 1 to 1 with logic further down, combine?
-                    if self.graph.has_node(&name) {
-                        if let Some(from) = from {
-                            self.graph.add_dependency(&from, &name);
-                        }
+            // Cached subgraphs were cut at depths relative to another root
+            let cached = self.options.max_depth.is_none() && self.cache.as_ref().is_some_and(|cache| cache.has_node(&name));
+            if cached {
+                // Process the parent and see if that adds the node first
+                self._process_parent(from, &name, edge)?;
 
-                        // Done!
-                        return
+                // Spooky!
+                // TODO: This logic is 1 to 1 with logic further down, combine?
+                if self.graph.has_node(&name) {
+                    if let Some(from) = from {
+                        self.graph.add_dependency(from, &name, edge.clone())?;
                     }
 
-                    // Other wise need to add ourselves
-                    let cache = self.cache.as_ref().unwrap();
-                    if let Some(from) = from {
-                        let subgraph = cache.clone_from(&name);
+                    // Done!
+                    return Ok(())
+                }
 
-                        // Track metadata
-                        let deps_added = subgraph.num_dependencies();
-                        self.metadata.from_cache += deps_added + 1;
-                        self.metadata.processed += deps_added;
+                // Other wise need to add ourselves
+                let cache = self.cache.as_ref().unwrap();
+                if let Some(from) = from {
+                    let subgraph = cache.clone_from(&name)?;
 
-                        self.graph.add_graph(
-                            from,
-                            &name,
-                            subgraph
-                        );
+                    // Track metadata
+                    let deps_added = subgraph.num_dependencies();
+                    self.metadata.from_cache += deps_added + 1;
+                    self.metadata.processed += deps_added;
 
-                        // Done!
-                        return
-                    } else {
-                        panic!("Adding graph without `from` is not implemented")
-                    }
+                    self.graph.add_graph(
+                        from,
+                        &name,
+                        edge.clone(),
+                        subgraph
+                    )?;
+
+                    // Done!
+                    return Ok(())
+                } else {
+                    return Err(FastDepError::graph("Adding graph without `from` is not implemented".to_string()))
                 }
             }
         }
 
         // Process parent before anything else
-        self._process_parent(from, &name);
+        self._process_parent(from, &name, edge)?;
 
         // During processing of the parent, the current name may be added to the graph
         if self.graph.has_node(&name) {
             // Same as above, only update dependencies
             if let Some(from) = from {
-                self.graph.add_dependency(&from, &name)
+                self.graph.add_dependency(from, &name, edge.clone())?
             }
 
             // Done!
-            return
+            return Ok(())
         }
 
         // At this point we must add the node ourselves
         // None for depth to allow that to be resolved by add_dependency(...)
         let new_node = DepNode::new(spec.clone(), None);
-        let source = self._load_source(&new_node);
-        self.graph.add(new_node); // Can this be delayed, how about self reference?
+        let depth = from.and_then(|from| self.graph.nodes.get(from)).and_then(|node| node.borrow().depth).map(|depth| depth + 1);
+        let imports = if self.options.descends(&name, depth) {
+            self._load_imports(&new_node)
+        } else {
+            debug!("Not following imports of: {}", name);
+            if self.options.beyond_max_depth(depth) {
+                self.truncated.insert(name.clone());
+            }
+            Ok(None)
+        };
+        self.graph.add(new_node)?; // Can this be delayed, how about self reference?
 
         // Add dependency from current node, to this new one
         if let Some(from) = from {
             self.graph.add_dependency(
-                &from,
-                &name
-            );
+                from,
+                &name,
+                edge.clone()
+            )?;
         }
 
-        // Process all dependencies of new node
-        if let Some(source) = source {
-            self._process_imports(spec, source.as_str())
+        // Process all dependencies of new node, failures of this module should not stop the build
+        let result = match imports {
+            Ok(Some(stmts)) => self._process_imports(spec, stmts),
+            Ok(None) => Ok(()),
+            Err(err) => Err(err)
+        };
+        match result {
+            Err(err @ FastDepError::Graph { .. }) => return Err(err),
+            Err(err) => self.graph.add_error(&name, err),
+            Ok(()) => ()
         }
 
         // Done!
+        return Ok(())
     }
 
-    fn _process_parent(&mut self, from: Option<&String>, name: &str) {
+    fn _process_parent(&mut self, from: Option<&String>, name: &str, edge: &ImportEdge) -> Result<(), FastDepError> {
         let names: Vec<&str> = name.split(".").collect();
         let parent = names[..names.len() - 1].join(".");
-        if parent.len() != 0 {
-            self._process_dependency(from, parent.as_str());
+        if !parent.is_empty() {
+            self._process_dependency(from, parent.as_str(), edge)?;
         }
+
+        return Ok(())
     }
-}
\ No newline at end of file
+}
+
+// Source of a module which can be parsed, `None` for built-in, frozen, extension and bytecode modules
+pub fn load_source(spec: &ModuleSpec) -> Result<Option<String>, FastDepError> {
+    if let Some(path_str) = &spec.origin {
+        // Some origins we would be able to parse
+        if path_str == "built-in"  || path_str == "frozen" {
+            return Ok(None)
+        }
+
+        let source_path = Path::new(path_str);
+        // Only load python files
+        if let Some(ext) = source_path.extension() {
+            if ext != "py" {
+                return Ok(None)
+            }
+        } else {
+            warn!(
+                "Unable to load extension for spec '{}' with origin '{}' skipping.",
+                spec.name,
+                path_str
+            );
+            return Ok(None)
+        }
+
+        debug!("Loading file: {}", path_str);
+        let mut source_file = File::open(source_path).map_err(|err| {
+            warn!(
+                "Unable to load file for spec '{}' with origin '{}' skipping.",
+                spec.name,
+                path_str,
+            );
+            FastDepError::io(path_str, err)
+        })?;
+        let mut source = String::new();
+
+        source_file.read_to_string(&mut source).map_err(|err| FastDepError::io(path_str, err))?;
+        return Ok(Some(source))
+    }
+
+    return Ok(None)
+}
+
+// Parse errors name the module's origin
+pub fn parse_module(spec: &ModuleSpec, source: &str) -> Result<ParsedSource, FastDepError> {
+    parse_source(source).map_err(|err| match err {
+        FastDepError::Parse { message, .. } => FastDepError::Parse {
+            origin: spec.origin.clone(),
+            message: message
+        },
+        err => err
+    })
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use log::{debug, warn};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::FastDepError;
+use crate::importlib::{ModuleSpec, Resolver};
+use crate::minimal_parser::ParsedSource;
+
+pub const CACHE_FILE: &str = "fast_dep_cache.json";
+// Bumped whenever `ParsedSource` or the layout below changes, older caches are discarded
+const CACHE_VERSION: u32 = 4;
+
+// FNV-1a, unlike `DefaultHasher` the result is stable between Rust releases
+fn fnv1a(bytes: &[u8], mut hash: u64) -> u64 {
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+
+// (seconds, nanoseconds) since the epoch
+type Mtime = (u64, u32);
+
+// Coarsest mtime resolution of common file systems (FAT), edits closer than this to each other may keep the mtime
+const MTIME_GRANULARITY: u64 = 2;
+
+fn to_mtime(time: SystemTime) -> Option<Mtime> {
+    let since_epoch = time.duration_since(UNIX_EPOCH).ok()?;
+    Some((since_epoch.as_secs(), since_epoch.subsec_nanos()))
+}
+
+fn mtime(path: &Path) -> Option<Mtime> {
+    to_mtime(fs::metadata(path).ok()?.modified().ok()?)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct FileStamp {
+    mtime: Mtime,
+    size: u64,
+    hash: u64,
+    // When the content was hashed
+    checked: Mtime,
+}
+
+impl FileStamp {
+    fn new(mtime: Mtime, source: &str) -> FileStamp {
+        FileStamp {
+            mtime: mtime,
+            size: source.len() as u64,
+            hash: fnv1a(source.as_bytes(), FNV_OFFSET),
+            checked: to_mtime(SystemTime::now()).unwrap_or_default(),
+        }
+    }
+
+    // The file may have been written again after it was hashed without its mtime changing
+    fn is_racy(&self) -> bool {
+        self.checked.0 < self.mtime.0 + MTIME_GRANULARITY
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ModuleEntry {
+    stamp: FileStamp,
+    parsed: ParsedSource,
+}
+
+// A spec stays valid while the directory it was found in is unchanged
+#[derive(Serialize, Deserialize)]
+struct SpecEntry {
+    spec: ModuleSpec,
+    dir: String,
+    dir_mtime: Mtime,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CacheDocument {
+    version: u32,
+    // Fingerprint of the resolver configuration the specs were found with
+    resolver: u64,
+    // By origin
+    modules: HashMap<String, ModuleEntry>,
+    // By requested name
+    specs: HashMap<String, SpecEntry>,
+}
+
+// Search path entries are part of the fingerprint with their mtime so that adding a module to
+// any of them (which could shadow a cached spec) drops all cached specs
+fn resolver_fingerprint(resolver: &Resolver) -> u64 {
+    let mut hash = FNV_OFFSET;
+    for path in &resolver.search_paths {
+        hash = fnv1a(path.as_bytes(), hash);
+        hash = fnv1a(format!("{:?}", mtime(Path::new(path))).as_bytes(), hash);
+    }
+
+    let mut names: Vec<&String> = resolver.builtin_modules.iter()
+        .chain(resolver.frozen_modules.iter())
+        .chain(resolver.extension_suffixes.iter())
+        .chain(resolver.source_suffixes.iter())
+        .chain(resolver.bytecode_suffixes.iter())
+        .collect();
+    names.sort();
+
+    // Aliases come from the `sys.modules` of the running process and change between runs, specs of aliased names
+    // are not persisted instead
+    hash = fnv1a(format!("{:?}", names).as_bytes(), hash);
+    hash
+}
+
+// Directory listing which determined the spec, `None` for specs not backed by a single file
+fn spec_dir(spec: &ModuleSpec) -> Option<PathBuf> {
+    let origin = Path::new(spec.origin.as_ref()?);
+    if !origin.is_absolute() {
+        return None
+    }
+
+    // Packages are found in the directory containing the package directory
+    let dir = origin.parent()?;
+    if spec.is_package() {
+        return Some(dir.parent()?.to_path_buf())
+    }
+    Some(dir.to_path_buf())
+}
+
+/// Parsed imports (with `__all__`) and resolved specs persisted in a directory, shared between processes.
+///
+/// Imports of a file are reused while its mtime and size are unchanged, otherwise the file is read
+/// and they are only reused if the content hash still matches. Files hashed within the mtime granularity
+/// of their last modification are always read, an edit in the same tick keeps the mtime.
+pub struct DiskCache {
+    path: PathBuf,
+    document: CacheDocument,
+    dirty: bool,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl DiskCache {
+    pub fn load(dir: &Path, resolver: &Resolver) -> Result<DiskCache, FastDepError> {
+        let dir_str = dir.to_string_lossy();
+        fs::create_dir_all(dir).map_err(|err| FastDepError::io(&dir_str, err))?;
+
+        let path = dir.join(CACHE_FILE);
+        let fingerprint = resolver_fingerprint(resolver);
+        let mut document = match fs::read_to_string(&path) {
+            Ok(data) => match serde_json::from_str::<CacheDocument>(&data) {
+                Ok(document) if document.version == CACHE_VERSION => document,
+                Ok(_) => {
+                    debug!("Discarding cache with outdated version: {}", path.display());
+                    CacheDocument::default()
+                },
+                Err(err) => {
+                    warn!("Discarding unreadable cache '{}': {}", path.display(), err);
+                    CacheDocument::default()
+                }
+            },
+            Err(_) => CacheDocument::default()
+        };
+
+        if document.resolver != fingerprint {
+            document.specs.clear();
+        }
+        document.version = CACHE_VERSION;
+        document.resolver = fingerprint;
+
+        Ok(DiskCache {
+            path: path,
+            document: document,
+            dirty: false,
+            hits: 0,
+            misses: 0,
+        })
+    }
+
+    /// Seed `resolver` with every cached spec which is still valid.
+    pub fn seed(&mut self, resolver: &mut Resolver) {
+        self.document.specs.retain(|_, entry| mtime(Path::new(&entry.dir)) == Some(entry.dir_mtime));
+        for (name, entry) in &self.document.specs {
+            resolver.insert_spec(name, entry.spec.clone());
+        }
+    }
+
+    // Cheap check without reading the file
+    pub fn imports(&mut self, path: &str) -> Option<ParsedSource> {
+        let entry = self.document.modules.get(path)?;
+        let metadata = fs::metadata(path).ok()?;
+
+        if Some(entry.stamp.mtime) != mtime(Path::new(path)) || entry.stamp.size != metadata.len() {
+            return None
+        }
+        if entry.stamp.is_racy() {
+            let source = fs::read_to_string(path).ok()?;
+            return self.imports_for_source(path, &source)
+        }
+
+        self.hits += 1;
+        Some(entry.parsed.clone())
+    }
+
+    // Touched files keep their imports when the content is the same
+    pub fn imports_for_source(&mut self, path: &str, source: &str) -> Option<ParsedSource> {
+        let stamp = FileStamp::new(mtime(Path::new(path))?, source);
+
+        let entry = self.document.modules.get_mut(path)?;
+        if entry.stamp.hash != stamp.hash || entry.stamp.size != stamp.size {
+            return None
+        }
+
+        entry.stamp = stamp;
+        self.dirty = true;
+        self.hits += 1;
+        Some(entry.parsed.clone())
+    }
+
+    pub fn insert_imports(&mut self, path: &str, source: &str, parsed: ParsedSource) {
+        let Some(mtime) = mtime(Path::new(path)) else {
+            return
+        };
+
+        self.misses += 1;
+        self.dirty = true;
+        self.document.modules.insert(path.to_string(), ModuleEntry {
+            stamp: FileStamp::new(mtime, source),
+            parsed: parsed,
+        });
+    }
+
+    /// Record the specs `resolver` found which are not cached yet.
+    pub fn update_specs(&mut self, resolver: &Resolver) {
+        for (name, spec) in resolver.resolved() {
+            if self.document.specs.contains_key(name) || resolver.aliases.contains_key(name) {
+                continue;
+            }
+            let Some(dir) = spec_dir(spec) else {
+                continue
+            };
+            let Some(dir_mtime) = mtime(&dir) else {
+                continue
+            };
+
+            self.dirty = true;
+            self.document.specs.insert(name.clone(), SpecEntry {
+                spec: spec.clone(),
+                dir: dir.to_string_lossy().to_string(),
+                dir_mtime: dir_mtime,
+            });
+        }
+    }
+
+    pub fn save(&mut self) -> Result<(), FastDepError> {
+        if !self.dirty {
+            return Ok(())
+        }
+
+        let path_str = self.path.to_string_lossy().to_string();
+        let data = serde_json::to_string(&self.document).map_err(|err| FastDepError::Serialization {
+            message: err.to_string()
+        })?;
+
+        // Written next to the cache and renamed so concurrent processes never see a partial file
+        let tmp = self.path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&tmp, data).map_err(|err| FastDepError::io(&path_str, err))?;
+        fs::rename(&tmp, &self.path).map_err(|err| FastDepError::io(&path_str, err))?;
+
+        self.dirty = false;
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::FastDepError;
+use crate::importlib::{ModuleSpec, Resolver};
+use super::types::*;
+
+/// Where a module comes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleCategory {
+    Stdlib,
+    ThirdParty,
+    FirstParty,
+    // Compiled into the interpreter
+    BuiltIn,
+    // Compiled modules outside of the standard library
+    Extension,
+    // Missing modules and those without anything to tell where they come from
+    #[default]
+    Unknown,
+}
+
+impl ModuleCategory {
+    pub const ALL: [ModuleCategory; 6] = [
+        ModuleCategory::Stdlib,
+        ModuleCategory::ThirdParty,
+        ModuleCategory::FirstParty,
+        ModuleCategory::BuiltIn,
+        ModuleCategory::Extension,
+        ModuleCategory::Unknown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModuleCategory::Stdlib => "stdlib",
+            ModuleCategory::ThirdParty => "third_party",
+            ModuleCategory::FirstParty => "first_party",
+            ModuleCategory::BuiltIn => "built_in",
+            ModuleCategory::Extension => "extension",
+            ModuleCategory::Unknown => "unknown",
+        }
+    }
+
+    pub fn parse(category: &str) -> Option<ModuleCategory> {
+        ModuleCategory::ALL.into_iter().find(|known| known.as_str() == category)
+    }
+}
+
+pub(crate) fn parse_category(category: &str) -> PyResult<ModuleCategory> {
+    ModuleCategory::parse(category).ok_or_else(|| {
+        let known: Vec<&str> = ModuleCategory::ALL.iter().map(|known| known.as_str()).collect();
+        PyValueError::new_err(format!("Unknown category '{}', expected one of: {}", category, known.join(", ")))
+    })
+}
+
+// Installers put distributions in directories with these names, whatever the prefix
+const SITE_DIRS: [&str; 2] = ["site-packages", "dist-packages"];
+
+/// Rules to tell the category of a module from its spec.
+///
+/// Locations are checked first, site-packages before the standard library which contains it, then the names of
+/// standard library modules for specs without a location. Without project roots every other file is first-party,
+/// with them only files under one of them are.
+#[derive(Clone, Default)]
+pub struct Classifier {
+    // Top-level names, as in `sys.stdlib_module_names`
+    pub stdlib_modules: HashSet<String>,
+    pub stdlib_paths: Vec<PathBuf>,
+    pub site_paths: Vec<PathBuf>,
+    pub project_roots: Vec<PathBuf>,
+    pub extension_suffixes: Vec<String>,
+}
+
+impl Classifier {
+    pub fn for_resolver(resolver: &Resolver) -> Classifier {
+        Classifier {
+            extension_suffixes: resolver.extension_suffixes.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn add_project_root(&mut self, root: &Path) {
+        let root = root.canonicalize().unwrap_or(root.to_path_buf());
+        if !self.project_roots.contains(&root) {
+            self.project_roots.push(root);
+        }
+    }
+
+    fn _under(paths: &[PathBuf], location: &Path) -> bool {
+        paths.iter().any(|path| location.starts_with(path))
+    }
+
+    pub fn classify(&self, spec: &ModuleSpec, missing: bool) -> ModuleCategory {
+        if missing {
+            return ModuleCategory::Unknown
+        }
+
+        let top_level = spec.name.split('.').next().unwrap();
+        let location = match spec.origin.as_deref() {
+            Some("built-in") => return ModuleCategory::BuiltIn,
+            Some("frozen") => return ModuleCategory::Stdlib,
+            Some(origin) => Some(PathBuf::from(origin)),
+            // Namespace packages
+            None => spec.submodule_search_locations.as_ref()
+                .and_then(|locations| locations.first())
+                .map(PathBuf::from),
+        };
+
+        let Some(location) = location else {
+            if spec.name == "<terminal>" {
+                return ModuleCategory::FirstParty
+            }
+            if self.stdlib_modules.contains(top_level) {
+                return ModuleCategory::Stdlib
+            }
+            return ModuleCategory::Unknown
+        };
+
+        let in_site_dir = location.components().any(|part| SITE_DIRS.iter().any(|dir| part.as_os_str() == *dir));
+        let in_project = Classifier::_under(&self.project_roots, &location);
+        let category = if in_site_dir || Classifier::_under(&self.site_paths, &location) {
+            ModuleCategory::ThirdParty
+        } else if Classifier::_under(&self.stdlib_paths, &location) || (self.stdlib_modules.contains(top_level) && !in_project) {
+            ModuleCategory::Stdlib
+        } else if in_project || self.project_roots.is_empty() {
+            ModuleCategory::FirstParty
+        } else {
+            ModuleCategory::ThirdParty
+        };
+
+        let origin = spec.origin.as_deref().unwrap_or("");
+        let compiled = self.extension_suffixes.iter().any(|suffix| origin.ends_with(suffix.as_str()));
+        if compiled && category != ModuleCategory::Stdlib {
+            return ModuleCategory::Extension
+        }
+
+        return category
+    }
+}
+
+impl DepGraph {
+    /// Set the category of every node.
+    pub fn classify(&mut self, classifier: &Classifier) {
+        for node_cell in self.nodes.values() {
+            let mut node = node_cell.borrow_mut();
+            node.category = classifier.classify(&node.spec, node.missing);
+        }
+    }
+
+    /// Names of the modules in `category`, sorted.
+    pub fn by_category(&self, category: ModuleCategory) -> Vec<String> {
+        let mut names: Vec<String> = self.nodes.iter()
+            .filter(|(_, node_cell)| node_cell.borrow().category == category)
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Copy of the graph with only the modules in `categories` and the imports between them.
+    pub fn filter_categories(&self, categories: &[ModuleCategory]) -> Result<DepGraph, FastDepError> {
+        let mut filtered = DepGraph::new();
+        for node_cell in self.nodes.values() {
+            let node = node_cell.borrow();
+            if categories.contains(&node.category) {
+                filtered.add(node.clone())?;
+            }
+        }
+
+        for node_cell in filtered.nodes.values() {
+            let mut node = node_cell.borrow_mut();
+            node.dependencies.retain(|dep, _| filtered.has_node(dep));
+            node.dependents.retain(|dependent| filtered.has_node(dependent));
+        }
+
+        for (name, err) in &self.errors {
+            if filtered.has_node(name) {
+                filtered.errors.push((name.clone(), err.clone()));
+            }
+        }
+        for unresolved in &self.unresolved {
+            if filtered.has_node(&unresolved.importer) {
+                filtered.unresolved.push(unresolved.clone());
+            }
+        }
+        filtered.roots = self.roots.iter().filter(|root| filtered.has_node(root)).cloned().collect();
+
+        return Ok(filtered)
+    }
+}
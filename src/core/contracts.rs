@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+use pyo3::prelude::*;
+use serde::Deserialize;
+
+use crate::error::FastDepError;
+use super::patterns::{ModulePattern, any_matches};
+use super::types::*;
+
+/// Rule on the imports between parts of a project, modules are given as patterns (see `ModulePattern`).
+///
+/// Every rule applies to indirect imports too: `a -> b -> c` is an import of `c` by `a`.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Contract {
+    // Nothing in `source_modules` imports anything in `forbidden_modules`
+    Forbidden {
+        name: String,
+        source_modules: Vec<String>,
+        forbidden_modules: Vec<String>,
+        // Only direct imports are checked
+        #[serde(default)]
+        allow_indirect: bool,
+    },
+    // Layers are ordered from the highest to the lowest, a layer never imports those above it. With containers each
+    // layer is a submodule of every container (ex: `app` and `api` for `app.api`), containers are checked separately.
+    Layers {
+        name: String,
+        layers: Vec<String>,
+        #[serde(default)]
+        containers: Vec<String>,
+    },
+    // None of the modules import each other
+    Independence {
+        name: String,
+        modules: Vec<String>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContractsFile {
+    #[serde(default)]
+    contracts: Vec<Contract>,
+}
+
+impl Contract {
+    pub fn name(&self) -> &str {
+        match self {
+            Contract::Forbidden { name, .. } => name,
+            Contract::Layers { name, .. } => name,
+            Contract::Independence { name, .. } => name,
+        }
+    }
+}
+
+/// Contracts of a TOML file, each in a `[[contracts]]` table with a `name` and a `type`.
+pub fn load_contracts(path: &Path) -> Result<Vec<Contract>, FastDepError> {
+    let path_str = path.to_string_lossy().to_string();
+    let data = fs::read_to_string(path).map_err(|err| FastDepError::io(&path_str, err))?;
+    let file: ContractsFile = toml::from_str(&data).map_err(|err| FastDepError::Parse {
+        origin: Some(path_str),
+        message: err.message().to_string()
+    })?;
+
+    return Ok(file.contracts)
+}
+
+/// Import chain breaking a contract.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractViolation {
+    // Name of the contract
+    #[pyo3(get)]
+    pub contract: String,
+    // From a module the contract restricts to the one it may not import (ex: `[a, b, c]` for `a -> b -> c`)
+    #[pyo3(get)]
+    pub path: Vec<String>,
+    // First statement introducing each step of `path`
+    #[pyo3(get)]
+    pub edges: Vec<ImportEdge>,
+}
+
+#[pymethods]
+impl ContractViolation {
+    fn __repr__(&self) -> String {
+        format!("ContractViolation({}: {})", self.contract, self.path.join(" -> "))
+    }
+}
+
+impl DepGraph {
+    /// Every import chain breaking one of `contracts`, in the order of the contracts.
+    pub fn check_contracts(&self, contracts: &[Contract]) -> Vec<ContractViolation> {
+        let mut violations = vec![];
+
+        for contract in contracts {
+            match contract {
+                Contract::Forbidden { name, source_modules, forbidden_modules, allow_indirect } => {
+                    let sources = self._matching(source_modules);
+                    let targets = &self._matching(forbidden_modules) - &sources;
+                    violations.extend(self._violations(name, &sources, &targets, &HashSet::new(), *allow_indirect));
+                },
+                Contract::Layers { name, layers, containers } => {
+                    let groups: Vec<Vec<String>> = if containers.is_empty() {
+                        vec![layers.clone()]
+                    } else {
+                        containers.iter()
+                            .map(|container| layers.iter().map(|layer| format!("{}.{}", container, layer)).collect())
+                            .collect()
+                    };
+
+                    for group in groups {
+                        let members: Vec<HashSet<String>> = group.iter()
+                            .map(|layer| self._matching(std::slice::from_ref(layer)))
+                            .collect();
+                        // Lower layers importing higher ones
+                        for (higher, lower) in (0..members.len()).flat_map(|j| (0..j).map(move |i| (i, j))) {
+                            violations.extend(self._between(name, &members, lower, higher));
+                        }
+                    }
+                },
+                Contract::Independence { name, modules } => {
+                    let members: Vec<HashSet<String>> = modules.iter()
+                        .map(|module| self._matching(std::slice::from_ref(module)))
+                        .collect();
+                    for (from, to) in (0..members.len()).flat_map(|i| (0..members.len()).map(move |j| (i, j))) {
+                        if from != to {
+                            violations.extend(self._between(name, &members, from, to));
+                        }
+                    }
+                },
+            }
+        }
+
+        return violations
+    }
+
+    fn _matching(&self, patterns: &[String]) -> HashSet<String> {
+        let patterns: Vec<ModulePattern> = patterns.iter().map(|pattern| ModulePattern::parse(pattern)).collect();
+        self.nodes.keys().filter(|name| any_matches(&patterns, name)).cloned().collect()
+    }
+
+    // Chains from `members[from]` to `members[to]` which do not go through any other member (ex: the layer between
+    // them), those are reported on their own
+    fn _between(&self, contract: &str, members: &[HashSet<String>], from: usize, to: usize) -> Vec<ContractViolation> {
+        let sources = &members[from];
+        let targets = &members[to] - sources;
+        let blocked: HashSet<String> = members.iter()
+            .flatten()
+            .filter(|name| !sources.contains(*name))
+            .cloned()
+            .collect();
+
+        self._violations(contract, sources, &targets, &blocked, false)
+    }
+
+    // Every import of one of `targets` by a module reachable from `sources` without going through `blocked`, with the
+    // shortest chain leading to it
+    fn _violations(
+        &self,
+        contract: &str,
+        sources: &HashSet<String>,
+        targets: &HashSet<String>,
+        blocked: &HashSet<String>,
+        direct_only: bool
+    ) -> Vec<ContractViolation> {
+        let mut violations = vec![];
+        if targets.is_empty() {
+            return violations
+        }
+
+        let mut sorted_sources: Vec<&String> = sources.iter().collect();
+        sorted_sources.sort();
+        let mut parents: HashMap<String, Option<String>> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for source in sorted_sources {
+            parents.insert(source.clone(), None);
+            queue.push_back(source.clone());
+        }
+
+        while let Some(name) = queue.pop_front() {
+            let deps = self._sorted_dependencies(&name);
+            for dep in &deps {
+                if targets.contains(dep) {
+                    // `from pkg import mod` imports `pkg` and `pkg.mod`, only the submodule is reported
+                    let line = self._first_edge(&name, dep).line;
+                    let prefix = format!("{}.", dep);
+                    let has_submodule = deps.iter().any(|other| {
+                        other.starts_with(&prefix) && targets.contains(other) && self._first_edge(&name, other).line == line
+                    });
+                    if !has_submodule {
+                        violations.push(self._violation(contract, &parents, &name, dep));
+                    }
+                } else if !direct_only && !blocked.contains(dep) && !parents.contains_key(dep) {
+                    parents.insert(dep.clone(), Some(name.clone()));
+                    queue.push_back(dep.clone());
+                }
+            }
+        }
+
+        violations
+    }
+
+    fn _violation(&self, contract: &str, parents: &HashMap<String, Option<String>>, importer: &str, target: &str) -> ContractViolation {
+        let mut path = vec![target.to_string(), importer.to_string()];
+        while let Some(Some(parent)) = parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+
+        let edges = path.windows(2).map(|step| self._first_edge(&step[0], &step[1])).collect();
+        ContractViolation {
+            contract: contract.to_string(),
+            path: path,
+            edges: edges,
+        }
+    }
+}
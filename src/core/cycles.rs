@@ -0,0 +1,195 @@
+use std::collections::{HashMap, VecDeque};
+
+use pyo3::prelude::*;
+
+use super::types::*;
+
+/// A group of modules which (transitively) import each other.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportCycle {
+    // Every module of the strongly connected component, sorted
+    #[pyo3(get)]
+    pub modules: Vec<String>,
+    // Shortest cycle through the component, closed (ex: `[a, b, a]`), only computed on request
+    #[pyo3(get)]
+    pub path: Option<Vec<String>>,
+    // First statement introducing each step of `path`
+    #[pyo3(get)]
+    pub edges: Option<Vec<ImportEdge>>,
+}
+
+#[pymethods]
+impl ImportCycle {
+    fn __len__(&self) -> usize {
+        self.modules.len()
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.path {
+            Some(path) => format!("ImportCycle({})", path.join(" -> ")),
+            None => format!("ImportCycle({})", self.modules.join(", ")),
+        }
+    }
+}
+
+impl DepGraph {
+    /// Strongly connected components (Tarjan), including single modules which are not part of a cycle.
+    ///
+    /// Components and the modules in them are sorted by name so results are stable between builds.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        let mut names: Vec<&String> = self.nodes.keys().collect();
+        names.sort();
+        let index_of: HashMap<&str, usize> = names.iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i))
+            .collect();
+        let successors: Vec<Vec<usize>> = names.iter().map(|name| {
+            let node = self.nodes.get(*name).unwrap().borrow();
+            let mut deps: Vec<usize> = node.dependencies.keys()
+                .filter_map(|dep| index_of.get(dep.as_str()).copied())
+                .collect();
+            deps.sort();
+            deps
+        }).collect();
+
+        let mut counter = 0;
+        let mut index: Vec<Option<usize>> = vec![None; names.len()];
+        let mut lowlink = vec![0; names.len()];
+        let mut on_stack = vec![false; names.len()];
+        let mut stack = vec![];
+        let mut components = vec![];
+
+        // Iterative to keep deep import chains from overflowing the call stack
+        for start in 0..names.len() {
+            if index[start].is_some() {
+                continue;
+            }
+
+            // (node, position of the next successor to visit)
+            let mut work = vec![(start, 0)];
+            while let Some((v, i)) = work.pop() {
+                if i == 0 {
+                    index[v] = Some(counter);
+                    lowlink[v] = counter;
+                    counter += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                }
+
+                if let Some(&w) = successors[v].get(i) {
+                    work.push((v, i + 1));
+                    match index[w] {
+                        None => work.push((w, 0)),
+                        Some(w_index) if on_stack[w] => lowlink[v] = lowlink[v].min(w_index),
+                        Some(_) => ()
+                    }
+                    continue;
+                }
+
+                // All successors visited, `v` is the root of a component if nothing reached further back
+                if Some(lowlink[v]) == index[v] {
+                    let mut component = vec![];
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        component.push(names[w].clone());
+                        if w == v {
+                            break
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+                if let Some(&(parent, _)) = work.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[v]);
+                }
+            }
+        }
+
+        components.sort();
+        return components
+    }
+
+    /// Import cycles, one per strongly connected component with more than one module (or a module importing itself).
+    pub fn cycles(&self, shortest: bool) -> Vec<ImportCycle> {
+        let mut cycles = vec![];
+
+        for component in self.strongly_connected_components() {
+            if component.len() == 1 && !self._depends_on(&component[0], &component[0]) {
+                continue;
+            }
+
+            let (path, edges) = if shortest {
+                let path = self._shortest_cycle(&component);
+                let edges = path.windows(2)
+                    .map(|step| self._first_edge(&step[0], &step[1]))
+                    .collect();
+                (Some(path), Some(edges))
+            } else {
+                (None, None)
+            };
+
+            cycles.push(ImportCycle {
+                modules: component,
+                path: path,
+                edges: edges,
+            });
+        }
+
+        return cycles
+    }
+
+    fn _depends_on(&self, from: &str, on: &str) -> bool {
+        self.nodes.get(from).unwrap().borrow().dependencies.contains_key(on)
+    }
+
+    pub(crate) fn _first_edge(&self, from: &str, on: &str) -> ImportEdge {
+        self.nodes.get(from).unwrap().borrow().dependencies.get(on).unwrap()[0].clone()
+    }
+
+    // Breadth first search from every module back to itself, staying inside the component
+    fn _shortest_cycle(&self, component: &[String]) -> Vec<String> {
+        let mut best: Option<Vec<String>> = None;
+
+        for start in component {
+            let mut parents: HashMap<&str, &str> = HashMap::new();
+            let mut queue = VecDeque::from([start.as_str()]);
+
+            'search: while let Some(name) = queue.pop_front() {
+                let node = self.nodes.get(name).unwrap().borrow();
+                let mut deps: Vec<&String> = node.dependencies.keys().collect();
+                deps.sort();
+
+                for dep in deps {
+                    let Ok(position) = component.binary_search(dep) else {
+                        continue
+                    };
+                    let dep = component[position].as_str();
+
+                    if dep == start {
+                        let mut path = vec![start.clone()];
+                        let mut current = name;
+                        while current != start {
+                            path.push(current.to_string());
+                            current = parents[current];
+                        }
+                        path.push(start.clone());
+                        path.reverse();
+
+                        if best.as_ref().is_none_or(|best| path.len() < best.len()) {
+                            best = Some(path);
+                        }
+                        break 'search;
+                    }
+                    if !parents.contains_key(dep) {
+                        parents.insert(dep, name);
+                        queue.push_back(dep);
+                    }
+                }
+            }
+        }
+
+        // Every component with more than one module has a cycle through each of its modules
+        return best.unwrap()
+    }
+}
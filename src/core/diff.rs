@@ -0,0 +1,185 @@
+use std::collections::BTreeSet;
+
+use pyo3::prelude::*;
+use serde::Serialize;
+
+use crate::error::FastDepError;
+use super::types::*;
+
+/// Module present in both graphs at a different depth.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DepthChange {
+    #[pyo3(get)]
+    pub name: String,
+    // `None` when not reachable from the roots
+    #[pyo3(get)]
+    pub old: Option<i32>,
+    #[pyo3(get)]
+    pub new: Option<i32>,
+}
+
+/// Changes from one graph to another, see `DepGraph::diff`.
+///
+/// Edges are dependencies between modules, changes to the statements introducing them (ex: moved lines) are ignored.
+#[pyclass]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphDiff {
+    #[pyo3(get)]
+    pub added_nodes: Vec<String>,
+    #[pyo3(get)]
+    pub removed_nodes: Vec<String>,
+    #[pyo3(get)]
+    pub added_edges: Vec<(String, String)>,
+    #[pyo3(get)]
+    pub removed_edges: Vec<(String, String)>,
+    #[pyo3(get)]
+    pub depth_changes: Vec<DepthChange>,
+}
+
+#[derive(Serialize)]
+struct DiffDocument<'a> {
+    added_nodes: &'a Vec<String>,
+    removed_nodes: &'a Vec<String>,
+    added_edges: Vec<EdgeRecord<'a>>,
+    removed_edges: Vec<EdgeRecord<'a>>,
+    depth_changes: &'a Vec<DepthChange>,
+}
+
+#[derive(Serialize)]
+struct EdgeRecord<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+fn edge_records(edges: &[(String, String)]) -> Vec<EdgeRecord<'_>> {
+    edges.iter().map(|(from, to)| EdgeRecord { from: from, to: to }).collect()
+}
+
+fn depth_str(depth: Option<i32>) -> String {
+    match depth {
+        Some(depth) => depth.to_string(),
+        None => "unreachable".to_string()
+    }
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.depth_changes.is_empty()
+    }
+
+    /// One line per change, grouped by kind.
+    pub fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "No changes\n".to_string()
+        }
+
+        let mut out = String::new();
+        let mut section = |title: &str, lines: Vec<String>| {
+            if !lines.is_empty() {
+                out.push_str(&format!("{} ({}):\n", title, lines.len()));
+                for line in lines {
+                    out.push_str(&format!("  {}\n", line));
+                }
+            }
+        };
+
+        section("Added modules", self.added_nodes.iter().map(|name| format!("+ {}", name)).collect());
+        section("Removed modules", self.removed_nodes.iter().map(|name| format!("- {}", name)).collect());
+        section("Added imports", self.added_edges.iter().map(|(from, to)| format!("+ {} -> {}", from, to)).collect());
+        section("Removed imports", self.removed_edges.iter().map(|(from, to)| format!("- {} -> {}", from, to)).collect());
+        section("Depth changes", self.depth_changes.iter().map(|change| {
+            format!("~ {}: {} -> {}", change.name, depth_str(change.old), depth_str(change.new))
+        }).collect());
+
+        out
+    }
+
+    pub fn to_json(&self, pretty: bool) -> Result<String, FastDepError> {
+        let document = DiffDocument {
+            added_nodes: &self.added_nodes,
+            removed_nodes: &self.removed_nodes,
+            added_edges: edge_records(&self.added_edges),
+            removed_edges: edge_records(&self.removed_edges),
+            depth_changes: &self.depth_changes,
+        };
+        let result = if pretty {
+            serde_json::to_string_pretty(&document)
+        } else {
+            serde_json::to_string(&document)
+        };
+
+        result.map_err(|err| FastDepError::Serialization { message: err.to_string() })
+    }
+}
+
+#[pymethods]
+impl GraphDiff {
+    #[pyo3(name = "to_text")]
+    fn py_to_text(&self) -> String {
+        self.to_text()
+    }
+
+    #[pyo3(name = "to_json")]
+    fn py_to_json(&self, pretty: Option<bool>) -> PyResult<String> {
+        Ok(self.to_json(pretty.unwrap_or(false))?)
+    }
+
+    fn __bool__(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn __str__(&self) -> String {
+        self.to_text()
+    }
+}
+
+impl DepGraph {
+    // Dependencies between modules of the graph, sorted
+    fn _edge_set(&self) -> BTreeSet<(String, String)> {
+        let mut edges = BTreeSet::new();
+        for (name, node_cell) in &self.nodes {
+            for dep in node_cell.borrow().dependencies.keys() {
+                if self.has_node(dep) {
+                    edges.insert((name.clone(), dep.clone()));
+                }
+            }
+        }
+        edges
+    }
+
+    /// Changes from this graph (ex: built on a base branch) to `other` (ex: built on a head branch).
+    pub fn diff(&self, other: &DepGraph) -> GraphDiff {
+        let names: BTreeSet<&String> = self.nodes.keys().collect();
+        let other_names: BTreeSet<&String> = other.nodes.keys().collect();
+        let edges = self._edge_set();
+        let other_edges = other._edge_set();
+
+        let depth_changes = names.intersection(&other_names)
+            .filter_map(|name| {
+                let old = self.nodes.get(*name).unwrap().borrow().depth;
+                let new = other.nodes.get(*name).unwrap().borrow().depth;
+                if old == new {
+                    return None
+                }
+                Some(DepthChange {
+                    name: name.to_string(),
+                    old: old,
+                    new: new,
+                })
+            })
+            .collect();
+
+        GraphDiff {
+            added_nodes: other_names.difference(&names).map(|name| name.to_string()).collect(),
+            removed_nodes: names.difference(&other_names).map(|name| name.to_string()).collect(),
+            added_edges: other_edges.difference(&edges).cloned().collect(),
+            removed_edges: edges.difference(&other_edges).cloned().collect(),
+            depth_changes: depth_changes,
+        }
+    }
+}
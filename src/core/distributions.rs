@@ -0,0 +1,26 @@
+use std::collections::BTreeSet;
+
+use crate::importlib::{Distribution, Resolver};
+use super::classify::ModuleCategory;
+use super::types::*;
+
+impl DepGraph {
+    /// Set the distribution of every installed module, nodes must have been classified first.
+    pub fn attach_distributions(&mut self, resolver: &mut Resolver) {
+        for node_cell in self.nodes.values() {
+            let mut node = node_cell.borrow_mut();
+            // Modules of the project or the interpreter may share a top-level name with a distribution
+            if matches!(node.category, ModuleCategory::ThirdParty | ModuleCategory::Extension) {
+                node.distribution = resolver.distribution(&node.spec, true);
+            }
+        }
+    }
+
+    /// Every distribution providing a module of the graph, sorted by name.
+    pub fn distributions(&self) -> Vec<Distribution> {
+        let distributions: BTreeSet<Distribution> = self.nodes.values()
+            .filter_map(|node_cell| node_cell.borrow().distribution.clone())
+            .collect();
+        distributions.into_iter().collect()
+    }
+}
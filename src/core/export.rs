@@ -0,0 +1,254 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+use super::classify::ModuleCategory;
+use super::types::*;
+
+#[derive(Clone, Default)]
+pub struct ExportOptions {
+    // Leave out nodes further than this from the root (nodes without a depth are left out too)
+    pub max_depth: Option<i32>,
+    // Draw every top-level package as a single node
+    pub collapse_packages: bool,
+    // Fill nodes by category, see `ModuleCategory`
+    pub color: bool,
+}
+
+// (category, DOT / Mermaid fill colour)
+const MISSING: (&str, &str) = ("missing", "#f8cecc");
+
+fn node_category(node: &DepNode) -> (&'static str, &'static str) {
+    if node.missing {
+        return MISSING
+    }
+
+    let fill = match node.category {
+        ModuleCategory::Stdlib => "#dae8fc",
+        ModuleCategory::ThirdParty => "#fff2cc",
+        ModuleCategory::FirstParty => "#d5e8d4",
+        ModuleCategory::BuiltIn => "#e1d5e7",
+        ModuleCategory::Extension => "#ffe6cc",
+        ModuleCategory::Unknown => "#f5f5f5",
+    };
+    (node.category.as_str(), fill)
+}
+
+fn top_level(name: &str) -> &str {
+    name.split('.').next().unwrap()
+}
+
+// Nodes and edges left after applying the options, sorted for stable output
+struct ExportView {
+    // name -> (top-level package, category)
+    nodes: BTreeMap<String, (String, (&'static str, &'static str))>,
+    edges: BTreeSet<(String, String)>,
+}
+
+impl DepGraph {
+    fn _export_view(&self, options: &ExportOptions) -> ExportView {
+        let included = |node: &DepNode| match options.max_depth {
+            Some(max_depth) => node.depth.is_some_and(|depth| depth <= max_depth),
+            None => true,
+        };
+        let display_name = |name: &str| if options.collapse_packages {
+            top_level(name).to_string()
+        } else {
+            name.to_string()
+        };
+
+        let mut names: Vec<&String> = self.nodes.keys().collect();
+        names.sort();
+
+        let mut nodes = BTreeMap::new();
+        let mut edges = BTreeSet::new();
+        for name in names {
+            let node = self.nodes.get(name).unwrap().borrow();
+            if !included(&node) {
+                continue;
+            }
+
+            let name = display_name(&node.name);
+            // Top-level modules have no parent, they form a package of their own
+            let package = if node.spec.parent.is_empty() {
+                top_level(&node.name).to_string()
+            } else {
+                top_level(&node.spec.parent).to_string()
+            };
+            nodes.entry(name.clone()).or_insert((package, node_category(&node)));
+
+            for dep in node.dependencies.keys() {
+                let Some(dep_cell) = self.nodes.get(dep) else {
+                    continue
+                };
+                if !included(&dep_cell.borrow()) {
+                    continue;
+                }
+
+                let dep = display_name(dep);
+                if dep != name || !options.collapse_packages {
+                    edges.insert((name.clone(), dep));
+                }
+            }
+        }
+
+        ExportView {
+            nodes: nodes,
+            edges: edges,
+        }
+    }
+
+    /// Graphviz DOT, modules of the same top-level package are grouped in a cluster.
+    pub fn to_dot(&self, options: &ExportOptions) -> String {
+        let view = self._export_view(options);
+        let mut out = String::new();
+
+        writeln!(out, "digraph dependencies {{").unwrap();
+        writeln!(out, "    node [shape=box];").unwrap();
+
+        let mut packages: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+        for (name, (package, _)) in &view.nodes {
+            packages.entry(package.as_str()).or_default().push(name);
+        }
+
+        for (package, names) in &packages {
+            let clustered = names.len() > 1 && !options.collapse_packages;
+            let indent = if clustered { "        " } else { "    " };
+            if clustered {
+                writeln!(out, "    subgraph \"cluster_{}\" {{", escape_dot(package)).unwrap();
+                writeln!(out, "        label=\"{}\";", escape_dot(package)).unwrap();
+            }
+            for name in names {
+                let (_, (_, fill)) = &view.nodes[*name];
+                if options.color {
+                    writeln!(out, "{}\"{}\" [style=filled, fillcolor=\"{}\"];", indent, escape_dot(name), fill).unwrap();
+                } else {
+                    writeln!(out, "{}\"{}\";", indent, escape_dot(name)).unwrap();
+                }
+            }
+            if clustered {
+                writeln!(out, "    }}").unwrap();
+            }
+        }
+
+        for (from, to) in &view.edges {
+            writeln!(out, "    \"{}\" -> \"{}\";", escape_dot(from), escape_dot(to)).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+
+        out
+    }
+
+    /// Mermaid flowchart, modules of the same top-level package are grouped in a subgraph.
+    pub fn to_mermaid(&self, options: &ExportOptions) -> String {
+        let view = self._export_view(options);
+        let mut out = String::new();
+
+        // Module names are not valid Mermaid ids (dots, `<terminal>`), number them instead
+        let ids: BTreeMap<&String, String> = view.nodes.keys()
+            .enumerate()
+            .map(|(i, name)| (name, format!("n{}", i)))
+            .collect();
+
+        writeln!(out, "flowchart LR").unwrap();
+
+        let mut packages: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+        for (name, (package, _)) in &view.nodes {
+            packages.entry(package.as_str()).or_default().push(name);
+        }
+
+        for (package, names) in &packages {
+            let clustered = names.len() > 1 && !options.collapse_packages;
+            let indent = if clustered { "        " } else { "    " };
+            if clustered {
+                writeln!(out, "    subgraph {}[\"{}\"]", escape_mermaid_id(package), escape_mermaid(package)).unwrap();
+            }
+            for name in names {
+                writeln!(out, "{}{}[\"{}\"]", indent, ids[name], escape_mermaid(name)).unwrap();
+            }
+            if clustered {
+                writeln!(out, "    end").unwrap();
+            }
+        }
+
+        for (from, to) in &view.edges {
+            writeln!(out, "    {} --> {}", ids[from], ids[to]).unwrap();
+        }
+
+        if options.color {
+            let mut by_category: BTreeMap<&str, (&str, Vec<&str>)> = BTreeMap::new();
+            for (name, (_, (category, fill))) in &view.nodes {
+                by_category.entry(category).or_insert((fill, vec![])).1.push(ids[name].as_str());
+            }
+            for (category, (fill, members)) in &by_category {
+                writeln!(out, "    classDef {} fill:{}", category, fill).unwrap();
+                writeln!(out, "    class {} {}", members.join(","), category).unwrap();
+            }
+        }
+
+        out
+    }
+
+    /// Indented tree from the roots, modules already shown with their imports are marked with `(*)`.
+    ///
+    /// `max_depth` limits the levels of the tree, not the depth of the nodes.
+    pub fn to_tree(&self, max_depth: Option<usize>) -> String {
+        let mut roots: Vec<String> = self.roots.iter().filter(|root| self.has_node(root)).cloned().collect();
+        // Graphs stored before roots were recorded
+        if roots.is_empty() {
+            roots = self.nodes.iter()
+                .filter(|(_, node_cell)| node_cell.borrow().depth == Some(0))
+                .map(|(name, _)| name.clone())
+                .collect();
+            roots.sort();
+        }
+
+        let mut out = String::new();
+        let mut expanded: HashSet<String> = HashSet::new();
+        for root in roots {
+            // (name, prefix of its line, prefix of its children's lines, level)
+            let mut stack = vec![(root, String::new(), String::new(), 0)];
+            while let Some((name, line_prefix, child_prefix, level)) = stack.pop() {
+                let node = self.nodes.get(&name).unwrap().borrow();
+                let deps = self._sorted_dependencies(&name);
+                let marker = if node.missing {
+                    " (missing)"
+                } else if !deps.is_empty() && expanded.contains(&name) {
+                    " (*)"
+                } else {
+                    ""
+                };
+                writeln!(out, "{}{}{}", line_prefix, name, marker).unwrap();
+
+                if !marker.is_empty() || max_depth.is_some_and(|max_depth| level >= max_depth) || deps.is_empty() {
+                    continue;
+                }
+                expanded.insert(name.clone());
+
+                // Reversed so the first dependency is printed first
+                let last = deps.len() - 1;
+                for (i, dep) in deps.into_iter().enumerate().rev() {
+                    let (branch, indent) = if i == last { ("└── ", "    ") } else { ("├── ", "│   ") };
+                    stack.push((dep, format!("{}{}", child_prefix, branch), format!("{}{}", child_prefix, indent), level + 1));
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;").replace('<', "#lt;").replace('>', "#gt;")
+}
+
+// Subgraph ids may not collide with the numbered node ids
+fn escape_mermaid_id(package: &str) -> String {
+    let id: String = package.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+    format!("pkg_{}", id)
+}
@@ -0,0 +1,87 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::patterns::glob_matches;
+use super::types::*;
+
+/// File names of test modules when none are given, pytest's default `python_files`.
+pub const DEFAULT_TEST_PATTERNS: [&str; 2] = ["test_*.py", "*_test.py"];
+
+// Changed files are often relative to a checkout (ex: `git diff --name-only`), origins to a search path
+fn _file_key(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    path.canonicalize()
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+impl DepGraph {
+    /// Modules loaded from any of `files`, sorted.
+    pub fn modules_for_files(&self, files: &[String]) -> Vec<String> {
+        let files: HashSet<PathBuf> = files.iter().map(|file| _file_key(file)).collect();
+
+        let mut modules: Vec<String> = self.nodes.iter()
+            .filter(|(_, node_cell)| {
+                node_cell.borrow().spec.origin.as_ref().is_some_and(|origin| files.contains(&_file_key(origin)))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        modules.sort();
+        modules
+    }
+
+    /// `modules` and every module importing any of them, directly or not.
+    ///
+    /// Importing a submodule runs its packages, so the submodules of an affected package are affected too.
+    pub fn affected_modules(&self, modules: &[String]) -> HashSet<String> {
+        let mut submodules: HashMap<&str, Vec<&String>> = HashMap::new();
+        for name in self.nodes.keys() {
+            if let Some((parent, _)) = name.rsplit_once('.') {
+                submodules.entry(parent).or_default().push(name);
+            }
+        }
+
+        let mut affected: HashSet<String> = HashSet::new();
+        let mut to_visit: Vec<String> = vec![];
+        for name in modules {
+            if self.has_node(name) && affected.insert(name.clone()) {
+                to_visit.push(name.clone());
+            }
+        }
+
+        while let Some(name) = to_visit.pop() {
+            let node = self.nodes.get(&name).unwrap().borrow();
+            let children = submodules.get(name.as_str()).into_iter().flatten().copied();
+            for dependent in node.dependents.iter().chain(children) {
+                if self.has_node(dependent) && affected.insert(dependent.clone()) {
+                    to_visit.push(dependent.clone());
+                }
+            }
+        }
+
+        affected
+    }
+
+    /// Test modules which could be affected by changes to `files`, sorted.
+    ///
+    /// Test modules are those whose file name matches one of `patterns` (ex: `test_*.py`), changed files which are
+    /// not the origin of a module are ignored.
+    pub fn affected_tests(&self, files: &[String], patterns: &[String]) -> Vec<String> {
+        let changed = self.modules_for_files(files);
+
+        let mut tests: Vec<String> = self.affected_modules(&changed).into_iter()
+            .filter(|name| {
+                let node = self.nodes.get(name).unwrap().borrow();
+                let file_name = node.spec.origin.as_ref()
+                    .and_then(|origin| Path::new(origin).file_name())
+                    .map(|file_name| file_name.to_string_lossy().to_string());
+
+                file_name.is_some_and(|file_name| {
+                    patterns.iter().any(|pattern| glob_matches(pattern.as_bytes(), file_name.as_bytes()))
+                })
+            })
+            .collect();
+        tests.sort();
+        tests
+    }
+}
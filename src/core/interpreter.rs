@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Command;
+use log::{debug};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde::Deserialize;
+
+use crate::error::FastDepError;
+use crate::importlib::{ModuleSpec, Resolver};
+use super::classify::Classifier;
+
+// Leaves the configuration as a JSON document in `config`, run by `python` or by the embedded interpreter
+const QUERY: &str = r#"
+import importlib.machinery, json, site, sys, sysconfig
+try:
+    import _imp
+    frozen = list(_imp._frozen_module_names())
+except (ImportError, AttributeError):
+    frozen = []
+aliases = {}
+for name, module in list(sys.modules.items()):
+    spec = getattr(module, '__spec__', None)
+    if spec is not None and spec.name != name:
+        aliases[name] = spec.name
+paths = sysconfig.get_paths()
+site_paths = [paths.get('purelib'), paths.get('platlib')]
+if hasattr(site, 'getsitepackages'):
+    site_paths.extend(site.getsitepackages())
+if hasattr(site, 'getusersitepackages'):
+    site_paths.append(site.getusersitepackages())
+config = json.dumps({
+    'search_paths': sys.path,
+    'builtin_modules': list(sys.builtin_module_names),
+    'frozen_modules': frozen,
+    'aliases': aliases,
+    'extension_suffixes': importlib.machinery.EXTENSION_SUFFIXES,
+    'source_suffixes': importlib.machinery.SOURCE_SUFFIXES,
+    'bytecode_suffixes': importlib.machinery.BYTECODE_SUFFIXES,
+    'stdlib_modules': sorted(getattr(sys, 'stdlib_module_names', [])),
+    'stdlib_paths': [p for p in (paths.get('stdlib'), paths.get('platstdlib')) if p],
+    'site_paths': [p for p in site_paths if p],
+})
+"#;
+
+/// Import configuration of a Python interpreter, read from the running one (see `from_python`) or from a separate
+/// process (see `query`, ex: for the `fast-dep` binary).
+#[derive(Clone, Debug, Deserialize)]
+pub struct InterpreterConfig {
+    pub search_paths: Vec<String>,
+    pub builtin_modules: Vec<String>,
+    pub frozen_modules: Vec<String>,
+    pub aliases: HashMap<String, String>,
+    pub extension_suffixes: Vec<String>,
+    pub source_suffixes: Vec<String>,
+    pub bytecode_suffixes: Vec<String>,
+    pub stdlib_modules: Vec<String>,
+    pub stdlib_paths: Vec<String>,
+    pub site_paths: Vec<String>,
+}
+
+impl InterpreterConfig {
+    /// Run `python` once and read its configuration, `sys.path` starts with the working directory.
+    pub fn query(python: &str) -> Result<InterpreterConfig, FastDepError> {
+        debug!("Querying import configuration of: {}", python);
+        let output = Command::new(python)
+            .args(["-c", &format!("{}print(config)\n", QUERY)])
+            .output()
+            .map_err(|err| FastDepError::io(python, err))?;
+
+        if !output.status.success() {
+            return Err(FastDepError::Io {
+                path: python.to_string(),
+                message: format!("exited with {}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim())
+            })
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|err| FastDepError::Io {
+            path: python.to_string(),
+            message: format!("unexpected output: {}", err)
+        })
+    }
+
+    /// Snapshot of the running interpreter.
+    pub fn from_python(py: Python<'_>) -> PyResult<InterpreterConfig> {
+        let globals = PyDict::new(py);
+        py.run(QUERY, Some(globals), None)?;
+        let config: String = match globals.get_item("config")? {
+            Some(config) => config.extract()?,
+            None => return Err(PyValueError::new_err("Interpreter query did not set `config`"))
+        };
+
+        serde_json::from_str(&config).map_err(|err| PyValueError::new_err(format!("Unexpected interpreter query output: {}", err)))
+    }
+
+    pub fn resolver(&self) -> Resolver {
+        let mut resolver = Resolver::new(self.search_paths.clone());
+        resolver.builtin_modules = self.builtin_modules.iter().cloned().collect();
+        resolver.frozen_modules = self.frozen_modules.iter().cloned().collect();
+        resolver.aliases = self.aliases.clone();
+        resolver.extension_suffixes = self.extension_suffixes.clone();
+        resolver.source_suffixes = self.source_suffixes.clone();
+        resolver.bytecode_suffixes = self.bytecode_suffixes.clone();
+        resolver
+    }
+
+    pub fn classifier(&self) -> Classifier {
+        Classifier {
+            stdlib_modules: self.stdlib_modules.iter().cloned().collect(),
+            stdlib_paths: self.stdlib_paths.iter().map(PathBuf::from).collect(),
+            site_paths: self.site_paths.iter().map(PathBuf::from).collect(),
+            project_roots: vec![],
+            extension_suffixes: self.extension_suffixes.clone(),
+        }
+    }
+}
+
+/// `importlib.util.find_spec` of the running interpreter, answered by a `Resolver` configured from it.
+#[pyfunction]
+#[pyo3(name = "find_spec")]
+pub fn py_find_spec(py: Python<'_>, name: &str) -> PyResult<Option<ModuleSpec>> {
+    let mut resolver = InterpreterConfig::from_python(py)?.resolver();
+    return Ok(resolver.find_spec(name))
+}
@@ -1,5 +1,47 @@
 pub mod types;
 pub use types::*;
 
+pub mod cycles;
+pub use cycles::*;
+
+mod paths;
+
+pub mod impact;
+pub use impact::*;
+
+pub mod diff;
+pub use diff::*;
+
+pub mod contracts;
+pub use contracts::*;
+
+pub mod classify;
+pub use classify::*;
+
+mod distributions;
+
+pub mod requirements;
+pub use requirements::*;
+
+pub mod interpreter;
+pub use interpreter::*;
+
+pub mod serialize;
+pub use serialize::*;
+
+pub mod export;
+pub use export::*;
+
+pub mod cache;
+pub use cache::*;
+
+mod prefetch;
+
+pub mod patterns;
+pub use patterns::*;
+
+pub mod project;
+pub use project::*;
+
 pub mod builder;
 pub use builder::*;
\ No newline at end of file
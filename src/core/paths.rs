@@ -0,0 +1,159 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::error::FastDepError;
+use super::types::*;
+
+impl DepGraph {
+    // Dependencies sorted by name so queries return the same chains between builds
+    pub(crate) fn _sorted_dependencies(&self, name: &str) -> Vec<String> {
+        let node = self.nodes.get(name).unwrap().borrow();
+        let mut deps: Vec<String> = node.dependencies.keys()
+            .filter(|dep| self.has_node(dep))
+            .cloned()
+            .collect();
+        deps.sort();
+        deps
+    }
+
+    // Every module from which `to` can be reached, following `dependents`
+    fn _reaching(&self, to: &str) -> HashSet<String> {
+        let mut reaching = HashSet::new();
+        reaching.insert(to.to_string());
+
+        let mut to_visit = vec![to.to_string()];
+        while let Some(name) = to_visit.pop() {
+            let node = self.nodes.get(&name).unwrap().borrow();
+            for dependent in &node.dependents {
+                if self.has_node(dependent) && reaching.insert(dependent.clone()) {
+                    to_visit.push(dependent.clone());
+                }
+            }
+        }
+
+        reaching
+    }
+
+    /// Distance of every module reachable from `root`, `root` included at 0.
+    pub fn depths_from(&self, root: &str) -> Result<HashMap<String, usize>, FastDepError> {
+        self._check_node(root)?;
+        Ok(self._bfs_depths(&[root.to_string()]))
+    }
+
+    // Multi source breadth first search
+    fn _bfs_depths(&self, roots: &[String]) -> HashMap<String, usize> {
+        let mut depths: HashMap<String, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for root in roots {
+            if self.has_node(root) && !depths.contains_key(root) {
+                depths.insert(root.clone(), 0);
+                queue.push_back(root.clone());
+            }
+        }
+
+        while let Some(name) = queue.pop_front() {
+            let depth = depths[&name];
+            for dep in self._sorted_dependencies(&name) {
+                if !depths.contains_key(&dep) {
+                    depths.insert(dep.clone(), depth + 1);
+                    queue.push_back(dep);
+                }
+            }
+        }
+
+        depths
+    }
+
+    /// Set every node's depth to its shortest distance from any of `roots`, unreachable nodes have none.
+    pub fn recompute_depths(&mut self, roots: &[String]) {
+        let depths = self._bfs_depths(roots);
+        for (name, node_cell) in &self.nodes {
+            node_cell.borrow_mut().depth = depths.get(name).map(|depth| *depth as i32);
+        }
+    }
+
+    /// Shortest import chain from `from` to `to` (both included), `None` if `to` is not reachable.
+    pub fn path(&self, from: &str, to: &str) -> Result<Option<Vec<String>>, FastDepError> {
+        self._check_node(from)?;
+        self._check_node(to)?;
+
+        let mut parents: HashMap<String, String> = HashMap::new();
+        let mut queue = VecDeque::from([from.to_string()]);
+        let mut found = from == to;
+
+        while let Some(name) = queue.pop_front() {
+            if found {
+                break
+            }
+            for dep in self._sorted_dependencies(&name) {
+                if dep == from || parents.contains_key(&dep) {
+                    continue;
+                }
+                parents.insert(dep.clone(), name.clone());
+                if dep == to {
+                    found = true;
+                    break
+                }
+                queue.push_back(dep);
+            }
+        }
+
+        if !found {
+            return Ok(None)
+        }
+
+        let mut path = vec![to.to_string()];
+        let mut current = to;
+        while current != from {
+            current = parents.get(current).unwrap();
+            path.push(current.to_string());
+        }
+        path.reverse();
+
+        return Ok(Some(path))
+    }
+
+    /// Up to `limit` import chains without repeated modules from `from` to `to`, shortest first.
+    pub fn all_paths(&self, from: &str, to: &str, limit: usize) -> Result<Vec<Vec<String>>, FastDepError> {
+        self._check_node(from)?;
+        self._check_node(to)?;
+
+        let mut paths = vec![];
+        if from == to {
+            paths.push(vec![from.to_string()]);
+            return Ok(paths)
+        }
+
+        // Only extend chains through modules which can still lead to `to`
+        let reaching = self._reaching(to);
+        if !reaching.contains(from) {
+            return Ok(paths)
+        }
+
+        // Breadth first over partial chains, so complete chains come out in order of length
+        let mut queue = VecDeque::from([vec![from.to_string()]]);
+        while let Some(path) = queue.pop_front() {
+            if paths.len() >= limit {
+                break
+            }
+
+            for dep in self._sorted_dependencies(path.last().unwrap()) {
+                if !reaching.contains(&dep) || path.contains(&dep) {
+                    continue;
+                }
+
+                let mut next = path.clone();
+                next.push(dep.clone());
+                if dep == to {
+                    paths.push(next);
+                    if paths.len() >= limit {
+                        break
+                    }
+                } else {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        return Ok(paths)
+    }
+}
@@ -0,0 +1,56 @@
+/// Module name pattern used to limit traversal.
+///
+/// Patterns with `*` (any characters, dots included) or `?` (one character) are matched against the whole name,
+/// others are prefixes on a package boundary: `numpy` matches `numpy` and `numpy.linalg` but not `numpy_financial`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ModulePattern {
+    Prefix(String),
+    Glob(String),
+}
+
+impl ModulePattern {
+    pub fn parse(pattern: &str) -> ModulePattern {
+        if pattern.contains(['*', '?']) {
+            ModulePattern::Glob(pattern.to_string())
+        } else {
+            ModulePattern::Prefix(pattern.trim_end_matches('.').to_string())
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            ModulePattern::Prefix(prefix) => {
+                name.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+            },
+            ModulePattern::Glob(glob) => glob_matches(glob.as_bytes(), name.as_bytes()),
+        }
+    }
+}
+
+// Backtracking over the last `*` only, enough as `*` can absorb anything the previous ones did
+pub(crate) fn glob_matches(glob: &[u8], name: &[u8]) -> bool {
+    let (mut g, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if g < glob.len() && (glob[g] == b'?' || glob[g] == name[n]) {
+            g += 1;
+            n += 1;
+        } else if g < glob.len() && glob[g] == b'*' {
+            star = Some((g, n));
+            g += 1;
+        } else if let Some((star_g, star_n)) = star {
+            g = star_g + 1;
+            n = star_n + 1;
+            star = Some((star_g, star_n + 1));
+        } else {
+            return false
+        }
+    }
+
+    glob[g..].iter().all(|c| *c == b'*')
+}
+
+pub fn any_matches(patterns: &[ModulePattern], name: &str) -> bool {
+    patterns.iter().any(|pattern| pattern.matches(name))
+}
@@ -0,0 +1,203 @@
+/*
+Parallel builds keep the graph construction serial, it depends on the order modules are discovered in (depths, parent
+packages, the in-memory cache), and only move the expensive part, reading and parsing files, to a thread pool.
+
+Modules are discovered one level at a time from the imports found so far, each level is parsed concurrently. The
+serial build then runs as usual taking imports from the prefetched results, which gives the same graph as a fully
+serial build.
+*/
+use std::collections::{HashMap, HashSet};
+use log::{debug};
+
+use rayon::prelude::*;
+
+use crate::importlib::*;
+use crate::minimal_parser::{ImportStmt, ParsedSource};
+use super::builder::{BuildOptions, load_source, parse_module};
+use super::cache::DiskCache;
+
+pub struct PrefetchedImports {
+    pub parsed: ParsedSource,
+    // Set when the file was read and parsed, `None` when the imports came from the disk cache
+    pub source: Option<String>,
+}
+
+// Every absolute name the builder may look up for these statements, parents included, and the modules star imports
+// are from (their submodules are only known once they are parsed)
+fn imported_names(spec: &ModuleSpec, stmts: &[ImportStmt], options: &BuildOptions) -> (Vec<String>, Vec<String>) {
+    let mut names = vec![];
+    let mut star_modules = vec![];
+
+    for stmt in stmts {
+        match stmt {
+            ImportStmt::Dynamic { .. } if !options.follow_dynamic => (),
+            ImportStmt::Import { context, .. } | ImportStmt::ImportFrom { context, .. } | ImportStmt::Dynamic { context, .. }
+                if (options.eager_only && !context.is_eager())
+                    || (options.exclude_type_checking && context.type_checking) => (),
+            ImportStmt::Import { names: aliases, .. } => {
+                names.extend(aliases.iter().map(|alias| alias.name.clone()));
+            },
+            ImportStmt::ImportFrom { module: Some(module), level: Some(level), names: aliases, .. } => {
+                let module_name = if *level == 0 {
+                    module.clone()
+                } else if spec.parent == "<terminal>" {
+                    continue;
+                } else {
+                    match resolve_name(module, &spec.parent, level) {
+                        Ok(module_name) => module_name,
+                        Err(_) => continue
+                    }
+                };
+
+                names.extend(aliases.iter()
+                    .filter(|alias| !alias.is_star())
+                    .map(|alias| format!("{}.{}", module_name, alias.name)));
+                if aliases.iter().any(|alias| alias.is_star()) {
+                    star_modules.push(module_name.clone());
+                }
+                names.push(module_name);
+            },
+            ImportStmt::ImportFrom { .. } => (),
+            ImportStmt::Dynamic { name, package, .. } => {
+                let level = name.len() - name.trim_start_matches('.').len();
+                if level == 0 {
+                    names.push(name.clone());
+                    continue;
+                }
+                let parent = package.as_deref().unwrap_or(&spec.parent);
+                if parent != "<terminal>" {
+                    if let Ok(module_name) = resolve_name(&name[level..], parent, &level) {
+                        names.push(module_name);
+                    }
+                }
+            },
+        }
+    }
+
+    (with_parents(names), star_modules)
+}
+
+// Parents are imported first
+fn with_parents(names: Vec<String>) -> Vec<String> {
+    let mut with_parents = vec![];
+    for name in names {
+        let mut end = 0;
+        while let Some(dot) = name[end..].find('.') {
+            end += dot;
+            with_parents.push(name[..end].to_string());
+            end += 1;
+        }
+        with_parents.push(name);
+    }
+
+    with_parents
+}
+
+// Submodules named in the `__all__` of a prefetched package
+fn star_names(
+    resolver: &mut Resolver,
+    prefetched: &HashMap<String, PrefetchedImports>,
+    module_name: &str
+) -> Vec<String> {
+    let Some(spec) = resolver.find_spec(module_name) else {
+        return vec![]
+    };
+    if !spec.is_package() {
+        return vec![]
+    }
+
+    spec.origin.as_ref()
+        .and_then(|origin| prefetched.get(origin))
+        .and_then(|prefetched| prefetched.parsed.dunder_all.as_ref())
+        .map(|names| names.iter().map(|name| format!("{}.{}", module_name, name)).collect())
+        .unwrap_or_default()
+}
+
+/// Read and parse every module reachable from `stmts` (imported by `root`) on `pool`.
+///
+/// Results are keyed by origin, modules which fail to load are left out and handled by the serial build. Modules
+/// whose origin is in `known` (ex: reached from an earlier root) are neither parsed nor followed.
+pub fn prefetch(
+    resolver: &mut Resolver,
+    options: &BuildOptions,
+    mut disk_cache: Option<&mut DiskCache>,
+    root: &ModuleSpec,
+    stmts: &[ImportStmt],
+    known: &HashSet<String>,
+    pool: &rayon::ThreadPool,
+) -> HashMap<String, PrefetchedImports> {
+    let mut prefetched = HashMap::new();
+    let mut seen: HashSet<String> = known.clone();
+    let mut frontier: Vec<(ModuleSpec, Vec<ImportStmt>)> = vec![(root.clone(), stmts.to_vec())];
+    // Star imported by the previous level, parsed along with it
+    let mut star_modules: Vec<String> = vec![];
+    // Modules of the frontier are (at most) this far from the root, imports of the next level are not followed past `max_depth`
+    let mut depth = 0;
+
+    while (!frontier.is_empty() || !star_modules.is_empty()) && !options.beyond_max_depth(Some(depth + 1)) {
+        let mut names = vec![];
+        let mut next_star_modules = vec![];
+        for (spec, stmts) in &frontier {
+            let (imported, star) = imported_names(spec, stmts, options);
+            names.extend(imported);
+            next_star_modules.extend(star);
+        }
+        for module_name in star_modules {
+            names.extend(with_parents(star_names(resolver, &prefetched, &module_name)));
+        }
+        star_modules = next_star_modules;
+
+        // Resolution goes through the resolver's cache and stays on this thread
+        let mut to_parse = vec![];
+        let mut next = vec![];
+        for name in names {
+            let Some(spec) = resolver.find_spec(&name) else {
+                continue
+            };
+            if options.is_excluded(&name) || options.is_excluded(&spec.name) || !options.descends(&spec.name, None) {
+                continue;
+            }
+            let Some(origin) = spec.origin.clone() else {
+                continue
+            };
+            if !seen.insert(origin.clone()) {
+                continue;
+            }
+
+            let cached = disk_cache.as_mut().and_then(|disk_cache| disk_cache.imports(&origin));
+            match cached {
+                Some(parsed) => {
+                    next.push((spec, parsed.imports.clone()));
+                    prefetched.insert(origin, PrefetchedImports {
+                        parsed: parsed,
+                        source: None,
+                    });
+                },
+                None => to_parse.push(spec)
+            }
+        }
+
+        debug!("Prefetching {} modules", to_parse.len());
+        // Failures are dropped here, the serial build runs into them again and records them
+        let parsed: Vec<(ModuleSpec, String, ParsedSource)> = pool.install(|| {
+            to_parse.into_par_iter().filter_map(|spec| {
+                let source = load_source(&spec).ok()??;
+                let parsed = parse_module(&spec, &source).ok()?;
+                Some((spec, source, parsed))
+            }).collect()
+        });
+
+        for (spec, source, parsed) in parsed {
+            next.push((spec.clone(), parsed.imports.clone()));
+            prefetched.insert(spec.origin.unwrap(), PrefetchedImports {
+                parsed: parsed,
+                source: Some(source),
+            });
+        }
+
+        frontier = next;
+        depth += 1;
+    }
+
+    prefetched
+}
@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::Path;
+use log::{debug};
+
+use crate::error::FastDepError;
+use crate::importlib::ModuleSpec;
+use super::types::DepGraph;
+
+// Directories which hold build output or environments rather than project modules, unless they are packages
+const SKIPPED_DIRS: [&str; 8] = [
+    "__pycache__", "node_modules", "site-packages", "venv", "build", "dist", "env", "htmlcov"
+];
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// Specs of every module under a project directory, sorted by name.
+///
+/// With a `src/` layout modules under `src` are named relative to it, everything else relative to `root`.
+/// Directories without `__init__.py` are namespace packages. When `root` is itself a package its modules are
+/// named after it and the packages containing it.
+pub fn scan_project(root: &Path) -> Result<Vec<ModuleSpec>, FastDepError> {
+    let root = root.canonicalize().map_err(|err| FastDepError::io(&path_string(root), err))?;
+
+    let mut specs = vec![];
+
+    // Scanning a package directory, named after the packages containing it
+    let mut package = vec![];
+    let mut dir = root.as_path();
+    while dir.join("__init__.py").is_file() {
+        let Some(name) = dir.file_name().map(|name| name.to_string_lossy().to_string()) else {
+            break
+        };
+        package.insert(0, name);
+        dir = dir.parent().unwrap_or(dir);
+    }
+    if !package.is_empty() {
+        if !package.iter().all(|part| is_identifier(part)) {
+            return Ok(specs)
+        }
+        scan_dir(&root, &package, &mut specs)?;
+        let init = path_string(&root.join("__init__.py"));
+        specs.push(ModuleSpec::new(&package.join("."), Some(init), Some(vec![path_string(&root)])));
+        specs.sort_by(|a, b| a.name.cmp(&b.name));
+        return Ok(specs)
+    }
+
+    let src = root.join("src");
+    if src.is_dir() {
+        scan_dir(&src, &[], &mut specs)?;
+    }
+    scan_dir(&root, &[], &mut specs)?;
+
+    specs.sort_by(|a, b| a.name.cmp(&b.name));
+    // Same module found twice (ex: `src/pkg` and `pkg`), the `src` one wins like an installed project
+    specs.dedup_by(|a, b| a.name == b.name);
+
+    return Ok(specs)
+}
+
+fn scan_dir(dir: &Path, package: &[String], specs: &mut Vec<ModuleSpec>) -> Result<(), FastDepError> {
+    let entries = fs::read_dir(dir).map_err(|err| FastDepError::io(&path_string(dir), err))?;
+    let mut entries: Vec<_> = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+    entries.sort();
+
+    for path in entries {
+        let Some(file_name) = path.file_name().map(|name| name.to_string_lossy().to_string()) else {
+            continue
+        };
+
+        if path.is_dir() {
+            // `src` is scanned as its own root
+            let is_src = package.is_empty() && file_name == "src";
+            let init = path.join("__init__.py");
+            let skipped = SKIPPED_DIRS.contains(&file_name.as_str()) && !init.is_file();
+            if is_src || file_name.starts_with('.') || skipped || !is_identifier(&file_name) {
+                continue;
+            }
+
+            let mut sub_package = package.to_vec();
+            sub_package.push(file_name);
+            let name = sub_package.join(".");
+
+            let origin = if init.is_file() { Some(path_string(&init)) } else { None };
+            let before = specs.len();
+            scan_dir(&path, &sub_package, specs)?;
+
+            // Namespace directories are only packages when they hold modules
+            if origin.is_some() || specs.len() > before {
+                debug!("Found package '{}'", name);
+                specs.push(ModuleSpec::new(&name, origin, Some(vec![path_string(&path)])));
+            }
+        } else if let Some(stem) = file_name.strip_suffix(".py") {
+            if stem == "__init__" || !is_identifier(stem) {
+                continue;
+            }
+
+            let mut parts = package.to_vec();
+            parts.push(stem.to_string());
+            specs.push(ModuleSpec::new(&parts.join("."), Some(path_string(&path)), None));
+        }
+    }
+
+    return Ok(())
+}
+
+impl DepGraph {
+    /// Roots which no module imports, importing a submodule imports its packages too.
+    pub fn orphans(&self) -> Vec<String> {
+        let imported: Vec<&String> = self.nodes.iter()
+            .filter(|(_, node_cell)| !node_cell.borrow().dependents.is_empty())
+            .map(|(name, _)| name)
+            .collect();
+
+        let mut orphans: Vec<String> = self.roots.iter()
+            .filter(|root| self.has_node(root))
+            .filter(|root| !imported.iter().any(|name| {
+                name == root || name.strip_prefix(root.as_str()).is_some_and(|rest| rest.starts_with('.'))
+            }))
+            .cloned()
+            .collect();
+        orphans.sort();
+        orphans
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use log::{debug};
+
+use pyo3::prelude::*;
+
+use crate::error::FastDepError;
+use super::classify::ModuleCategory;
+use super::types::*;
+
+// PEP 503, `Foo_Bar.baz` and `foo-bar-baz` are the same distribution
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::new();
+    for c in name.trim().chars() {
+        if c == '-' || c == '_' || c == '.' {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+// Name at the start of a PEP 508 requirement (`requests[socks]>=2.0; python_version < "3.8"`)
+fn requirement_name(requirement: &str) -> Option<&str> {
+    let requirement = requirement.trim();
+    let end = requirement.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+    if end == 0 {
+        return None
+    }
+    Some(&requirement[..end])
+}
+
+fn parse_error(path: &Path, message: String) -> FastDepError {
+    FastDepError::Parse {
+        origin: Some(path.to_string_lossy().to_string()),
+        message: message
+    }
+}
+
+/// Distributions a project declares it depends on, by normalized name.
+#[derive(Clone, Debug, Default)]
+pub struct DeclaredDependencies {
+    // The project's own distribution, its modules are never undeclared
+    pub project: Option<String>,
+    // normalized name -> (name as written, file declaring it)
+    pub dependencies: BTreeMap<String, (String, String)>,
+}
+
+impl DeclaredDependencies {
+    /// `[project.dependencies]` of a `pyproject.toml`.
+    pub fn from_pyproject(path: &Path) -> Result<DeclaredDependencies, FastDepError> {
+        let path_str = path.to_string_lossy().to_string();
+        let data = fs::read_to_string(path).map_err(|err| FastDepError::io(&path_str, err))?;
+        let document: toml::Table = data.parse().map_err(|err: toml::de::Error| parse_error(path, err.message().to_string()))?;
+
+        let mut declared = DeclaredDependencies::default();
+        let Some(project) = document.get("project") else {
+            return Ok(declared)
+        };
+        declared.project = project.get("name").and_then(|name| name.as_str()).map(normalize_name);
+
+        let dependencies = match project.get("dependencies") {
+            Some(toml::Value::Array(dependencies)) => dependencies,
+            Some(_) => return Err(parse_error(path, "`project.dependencies` must be an array of strings".to_string())),
+            None => return Ok(declared)
+        };
+        for dependency in dependencies {
+            let Some(requirement) = dependency.as_str() else {
+                return Err(parse_error(path, "`project.dependencies` must be an array of strings".to_string()))
+            };
+            declared._add(requirement, &path_str);
+        }
+
+        return Ok(declared)
+    }
+
+    /// Requirements of a pip `requirements*.txt`, files included with `-r` are read too.
+    pub fn add_requirements(&mut self, path: &Path) -> Result<(), FastDepError> {
+        self._add_requirements(path, &mut HashSet::new())
+    }
+
+    // Files including each other are only read once, `read` holds the canonical paths read so far
+    fn _add_requirements(&mut self, path: &Path, read: &mut HashSet<PathBuf>) -> Result<(), FastDepError> {
+        let path_str = path.to_string_lossy().to_string();
+        let canonical = path.canonicalize().map_err(|err| FastDepError::io(&path_str, err))?;
+        if !read.insert(canonical) {
+            debug!("Skipping requirements file read already: {}", path_str);
+            return Ok(())
+        }
+        let data = fs::read_to_string(path).map_err(|err| FastDepError::io(&path_str, err))?;
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for line in data.lines() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(included) = option_value(line, "-r", "--requirement") {
+                self._add_requirements(&dir.join(included), read)?;
+            } else if let Some(editable) = option_value(line, "-e", "--editable") {
+                match editable_name(dir, editable) {
+                    // Installing the project itself in development mode
+                    Some(name) if self.project.as_ref() == Some(&normalize_name(&name)) => (),
+                    Some(name) => self._add(&name, &path_str),
+                    None => debug!("Skipping editable requirement without a name: {}", line)
+                }
+            } else if line.starts_with('-') || (line.contains("://") && !line.contains('@')) {
+                // Options (`-e`, `--index-url`, ...) and bare URLs do not name a distribution
+                debug!("Skipping requirement line: {}", line);
+            } else {
+                self._add(line, &path_str);
+            }
+        }
+
+        return Ok(())
+    }
+
+    fn _add(&mut self, requirement: &str, file: &str) {
+        if let Some(name) = requirement_name(requirement) {
+            self.dependencies.entry(normalize_name(name)).or_insert((name.to_string(), file.to_string()));
+        }
+    }
+}
+
+// Like pip, `#` starts a comment at the start of a line or after whitespace, URL fragments (`#egg=`) are kept
+fn strip_comment(line: &str) -> &str {
+    let mut previous: Option<char> = None;
+    for (index, c) in line.char_indices() {
+        if c == '#' && previous.is_none_or(char::is_whitespace) {
+            return &line[..index]
+        }
+        previous = Some(c);
+    }
+    line
+}
+
+// Value of a `-r file.txt` / `-rfile.txt` / `--requirement file.txt` / `--requirement=file.txt` line
+fn option_value<'a>(line: &'a str, short: &str, long: &str) -> Option<&'a str> {
+    let value = if let Some(rest) = line.strip_prefix(long) {
+        match rest.strip_prefix('=') {
+            Some(value) => value,
+            None if rest.starts_with(char::is_whitespace) => rest,
+            None => return None
+        }
+    } else {
+        line.strip_prefix(short)?
+    };
+
+    let value = value.trim();
+    if value.is_empty() {
+        return None
+    }
+    Some(value)
+}
+
+// Distribution of an `-e` line, from the `#egg=` fragment of a URL or the `pyproject.toml` of a local directory
+fn editable_name(dir: &Path, editable: &str) -> Option<String> {
+    if let Some((_, fragment)) = editable.split_once("#egg=") {
+        let name = fragment.split('&').next().unwrap_or_default();
+        return requirement_name(name).map(|name| name.to_string())
+    }
+    if editable.contains("://") {
+        return None
+    }
+
+    let pyproject = dir.join(editable.strip_prefix("file:").unwrap_or(editable)).join("pyproject.toml");
+    if !pyproject.is_file() {
+        return None
+    }
+    DeclaredDependencies::from_pyproject(&pyproject).ok()?.project
+}
+
+/// Import of an installed distribution which is not declared.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct UndeclaredImport {
+    #[pyo3(get)]
+    pub distribution: String,
+    // Imported module provided by the distribution
+    #[pyo3(get)]
+    pub module: String,
+    // First-party module containing the import
+    #[pyo3(get)]
+    pub importer: String,
+    #[pyo3(get)]
+    pub edge: ImportEdge,
+}
+
+#[pymethods]
+impl UndeclaredImport {
+    fn __repr__(&self) -> String {
+        format!(
+            "<UndeclaredImport {} ({}) in {}:{}>",
+            self.distribution,
+            self.module,
+            self.edge.file.as_deref().unwrap_or(&self.importer),
+            self.edge.line
+        )
+    }
+}
+
+#[pyclass]
+#[derive(Clone, Debug, Default)]
+pub struct DependencyReport {
+    // One per import statement, sorted by importer and line
+    #[pyo3(get)]
+    pub undeclared: Vec<UndeclaredImport>,
+    // Declared dependencies (as written) no first-party module imports
+    #[pyo3(get)]
+    pub unused: Vec<String>,
+}
+
+#[pymethods]
+impl DependencyReport {
+    fn __bool__(&self) -> bool {
+        !self.undeclared.is_empty() || !self.unused.is_empty()
+    }
+}
+
+impl DepGraph {
+    /// Compare the distributions first-party modules import directly with those declared.
+    ///
+    /// Nodes need categories and distributions, imports of modules not mapped to a distribution are not checked.
+    pub fn check_dependencies(&self, declared: &DeclaredDependencies) -> DependencyReport {
+        let mut report = DependencyReport::default();
+        let mut used: HashSet<String> = HashSet::new();
+        let mut reported: HashSet<(String, String, Option<String>, usize, usize)> = HashSet::new();
+
+        let mut importers: Vec<&String> = self.nodes.keys().collect();
+        importers.sort();
+        for importer in importers {
+            let node = self.nodes.get(importer).unwrap().borrow();
+            if node.category != ModuleCategory::FirstParty {
+                continue;
+            }
+
+            let mut deps: Vec<&String> = node.dependencies.keys().collect();
+            deps.sort();
+            for dep in deps {
+                let Some(dep_cell) = self.nodes.get(dep) else {
+                    continue
+                };
+                let Some(distribution) = dep_cell.borrow().distribution.clone() else {
+                    continue
+                };
+
+                let name = normalize_name(&distribution.name);
+                used.insert(name.clone());
+                if declared.dependencies.contains_key(&name) || declared.project.as_ref() == Some(&name) {
+                    continue;
+                }
+
+                // `import pkg.sub` depends on `pkg` and `pkg.sub` through the same statement
+                for edge in node.dependencies.get(dep).unwrap() {
+                    let key = (name.clone(), importer.clone(), edge.file.clone(), edge.line, edge.column);
+                    if reported.insert(key) {
+                        report.undeclared.push(UndeclaredImport {
+                            distribution: distribution.name.clone(),
+                            module: dep.clone(),
+                            importer: importer.clone(),
+                            edge: edge.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        report.undeclared.sort_by(|a, b| {
+            (&a.importer, a.edge.line, a.edge.column).cmp(&(&b.importer, b.edge.line, b.edge.column))
+        });
+        report.unused = declared.dependencies.iter()
+            .filter(|(name, _)| !used.contains(*name))
+            .map(|(_, (written, _))| written.clone())
+            .collect();
+
+        return report
+    }
+}
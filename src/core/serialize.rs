@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::FastDepError;
+use crate::importlib::{Distribution, ModuleSpec};
+use super::classify::ModuleCategory;
+use super::types::*;
+
+/// Bumped on any incompatible change to the document layout, older documents are rejected.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct GraphDocument {
+    version: u32,
+    nodes: Vec<NodeRecord>,
+    edges: Vec<EdgeRecord>,
+    #[serde(default)]
+    errors: Vec<ErrorRecord>,
+    #[serde(default)]
+    unresolved: Vec<UnresolvedRecord>,
+    #[serde(default)]
+    roots: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NodeRecord {
+    name: String,
+    origin: Option<String>,
+    parent: String,
+    is_package: bool,
+    submodule_search_locations: Option<Vec<String>>,
+    depth: Option<i32>,
+    missing: bool,
+    #[serde(default)]
+    category: ModuleCategory,
+    #[serde(default)]
+    distribution: Option<Distribution>,
+}
+
+// One record per statement, a dependency introduced by several statements has several records
+#[derive(Serialize, Deserialize)]
+struct EdgeRecord {
+    from: String,
+    to: String,
+    #[serde(flatten)]
+    edge: ImportEdge,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ErrorRecord {
+    module: String,
+    error: FastDepError,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UnresolvedRecord {
+    name: String,
+    importer: String,
+    text: String,
+}
+
+fn serialization_error(err: serde_json::Error) -> FastDepError {
+    FastDepError::Serialization { message: err.to_string() }
+}
+
+impl DepGraph {
+    // Everything but the roots (kept in the order they were given) is sorted so the same graph
+    // always produces the same document, regardless of the order in which it was built
+    fn _to_document(&self) -> GraphDocument {
+        let mut names: Vec<&String> = self.nodes.keys().collect();
+        names.sort();
+
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        for name in names {
+            let node = self.nodes.get(name).unwrap().borrow();
+            nodes.push(NodeRecord {
+                name: node.name.clone(),
+                origin: node.spec.origin.clone(),
+                parent: node.spec.parent.clone(),
+                is_package: node.spec.is_package(),
+                submodule_search_locations: node.spec.submodule_search_locations.clone(),
+                depth: node.depth,
+                missing: node.missing,
+                category: node.category,
+                distribution: node.distribution.clone(),
+            });
+
+            let mut deps: Vec<&String> = node.dependencies.keys().collect();
+            deps.sort();
+            for dep in deps {
+                for edge in node.dependencies.get(dep).unwrap() {
+                    edges.push(EdgeRecord {
+                        from: node.name.clone(),
+                        to: dep.clone(),
+                        edge: edge.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut errors: Vec<ErrorRecord> = self.errors.iter().map(|(module, error)| ErrorRecord {
+            module: module.clone(),
+            error: error.clone(),
+        }).collect();
+        errors.sort_by_cached_key(|record| (record.module.clone(), record.error.to_string()));
+
+        let mut unresolved: Vec<UnresolvedRecord> = self.unresolved.iter().map(|unresolved| UnresolvedRecord {
+            name: unresolved.name.clone(),
+            importer: unresolved.importer.clone(),
+            text: unresolved.text.clone(),
+        }).collect();
+        unresolved.sort_by(|a, b| (&a.importer, &a.name, &a.text).cmp(&(&b.importer, &b.name, &b.text)));
+
+        GraphDocument {
+            version: SCHEMA_VERSION,
+            nodes: nodes,
+            edges: edges,
+            errors: errors,
+            unresolved: unresolved,
+            roots: self.roots.clone(),
+        }
+    }
+
+    pub fn to_json(&self, pretty: bool) -> Result<String, FastDepError> {
+        let document = self._to_document();
+        let result = if pretty {
+            serde_json::to_string_pretty(&document)
+        } else {
+            serde_json::to_string(&document)
+        };
+
+        result.map_err(serialization_error)
+    }
+
+    pub fn from_json(data: &str) -> Result<DepGraph, FastDepError> {
+        let document: GraphDocument = serde_json::from_str(data).map_err(serialization_error)?;
+        if document.version != SCHEMA_VERSION {
+            return Err(FastDepError::Serialization { message: format!(
+                "Unsupported schema version {} (expected {})",
+                document.version,
+                SCHEMA_VERSION
+            )})
+        }
+
+        let mut graph = DepGraph::new();
+        for record in document.nodes {
+            if graph.has_node(&record.name) {
+                return Err(FastDepError::Serialization {
+                    message: format!("Duplicate node: {}", record.name)
+                })
+            }
+
+            let spec = ModuleSpec {
+                name: record.name,
+                origin: record.origin,
+                parent: record.parent,
+                submodule_search_locations: record.submodule_search_locations,
+            };
+            let mut node = DepNode::new(spec, record.depth);
+            node.missing = record.missing;
+            node.category = record.category;
+            node.distribution = record.distribution;
+            graph.add(node)?;
+        }
+
+        // Depths are taken as stored, edges are linked without going through `add_dependency`
+        for record in document.edges {
+            for name in [&record.from, &record.to] {
+                if !graph.has_node(name) {
+                    return Err(FastDepError::Serialization {
+                        message: format!("Edge references unknown node: {}", name)
+                    })
+                }
+            }
+
+            graph.nodes.get(&record.from).unwrap().borrow_mut().add_edge(&record.to, record.edge);
+            graph.nodes.get(&record.to).unwrap().borrow_mut().dependents.insert(record.from);
+        }
+
+        for record in document.errors {
+            graph.add_error(&record.module, record.error);
+        }
+        for record in document.unresolved {
+            graph.add_unresolved(UnresolvedImport {
+                name: record.name,
+                importer: record.importer,
+                text: record.text,
+            });
+        }
+
+        graph.roots = document.roots;
+
+        return Ok(graph)
+    }
+}
@@ -4,8 +4,80 @@ use std::ops::Deref;
 use log::{debug};
 
 use pyo3::prelude::*;
-
+use serde::{Deserialize, Serialize};
+
+use crate::core::classify::{ModuleCategory, parse_category};
+use crate::core::contracts::{ContractViolation, load_contracts};
+use crate::core::cycles::ImportCycle;
+use crate::core::diff::GraphDiff;
+use crate::core::export::ExportOptions;
+use crate::core::impact::DEFAULT_TEST_PATTERNS;
+use crate::core::requirements::{DeclaredDependencies, DependencyReport};
+use crate::error::FastDepError;
 use crate::importlib;
+use crate::minimal_parser::ImportScope;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKind {
+    // `import x`
+    Import,
+    // `from x import y`
+    ImportFrom,
+    // `importlib.import_module("x")` and other calls naming a module with a string literal
+    Dynamic,
+}
+
+impl ImportKind {
+    // Same names as the `type` key of `parser.parse(...)` results
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportKind::Import => "import",
+            ImportKind::ImportFrom => "import_from",
+            ImportKind::Dynamic => "dynamic",
+        }
+    }
+}
+
+/// Where and how a dependency was introduced.
+///
+/// Dependencies on parent packages share the edge of the statement which caused them.
+#[pyclass]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportEdge {
+    // Origin of the importing module, `None` when building from a source string
+    #[pyo3(get)]
+    pub file: Option<String>,
+    #[pyo3(get)]
+    pub line: usize,
+    #[pyo3(get)]
+    pub column: usize,
+    pub kind: ImportKind,
+    #[pyo3(get)]
+    pub asname: Option<String>,
+    #[pyo3(get)]
+    pub level: usize,
+    pub scope: ImportScope,
+    // Under an `if` / `try` / `with` / loop block
+    #[pyo3(get)]
+    pub conditional: bool,
+    // Under `if TYPE_CHECKING:`, never executed at runtime
+    #[pyo3(get)]
+    pub type_checking: bool,
+}
+
+#[pymethods]
+impl ImportEdge {
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.kind.as_str()
+    }
+
+    #[getter]
+    fn scope(&self) -> &'static str {
+        self.scope.as_str()
+    }
+}
 
 #[pyclass]
 #[derive(Clone)]
@@ -14,12 +86,20 @@ pub struct DepNode {
     pub name: String,
     #[pyo3(get)]
     pub spec: importlib::ModuleSpec,
-    // The dependencies & dependents by spec.name
-    dependencies: HashSet<String>,
+    // The dependencies & dependents by spec.name, dependencies hold every statement introducing them
+    pub dependencies: HashMap<String, Vec<ImportEdge>>,
     #[pyo3(get)]
-    dependents: HashSet<String>,
+    pub dependents: HashSet<String>,
     #[pyo3(get)]
-    depth: Option<i32>
+    pub depth: Option<i32>,
+    // No spec could be found for this name, it is only known through the modules importing it
+    #[pyo3(get)]
+    pub missing: bool,
+    // Set once the build is done, see `Classifier`
+    pub category: ModuleCategory,
+    // Installed distribution providing a third-party module
+    #[pyo3(get)]
+    pub distribution: Option<importlib::Distribution>,
 }
 
 impl DepNode {
@@ -27,30 +107,60 @@ impl DepNode {
         DepNode {
             name: spec.name.clone(),
             spec: spec,
-            dependencies: HashSet::new(),
+            dependencies: HashMap::new(),
             dependents: HashSet::new(),
-            depth: depth // Allow for uninitialized depths
+            depth: depth, // Allow for uninitialized depths
+            missing: false,
+            category: ModuleCategory::Unknown,
+            distribution: None,
         }
     }
 
-    fn merge(&mut self, other: DepNode) {
+    pub fn missing(name: &str) -> DepNode {
+        let mut node = DepNode::new(importlib::ModuleSpec::new(name, None, None), None);
+        node.missing = true;
+
+        node
+    }
+
+    fn merge(&mut self, other: DepNode) -> Result<(), FastDepError> {
         // Few sanity checks
-        assert!(self.name == other.name);
-        if let Some(origin) = &self.spec.origin {
-            assert!(origin == &other.spec.origin.unwrap());
+        if self.name != other.name {
+            return Err(FastDepError::graph(format!("Attempted to merge node '{}' into '{}'", other.name, self.name)))
+        }
+        if self.spec.origin.is_some() && self.spec.origin != other.spec.origin {
+            return Err(FastDepError::graph(format!(
+                "Attempted to merge node '{}' loaded from different origins: {:?} and {:?}",
+                self.name,
+                self.spec.origin,
+                other.spec.origin
+            )))
         }
 
         // Merge data
-        self.dependencies.extend(other.dependencies);
+        for (name, edges) in other.dependencies {
+            for edge in edges {
+                self.add_edge(&name, edge);
+            }
+        }
         self.dependents.extend(other.dependents);
         if other.depth < self.depth {
             self.depth = other.depth
         }
+
+        return Ok(())
+    }
+
+    pub(crate) fn add_edge(&mut self, on: &str, edge: ImportEdge) {
+        let edges = self.dependencies.entry(on.to_string()).or_default();
+        if !edges.contains(&edge) {
+            edges.push(edge);
+        }
     }
 
     #[allow(dead_code)]
     fn is_root(&self) -> bool {
-        self.dependencies.len() == 0
+        self.dependencies.is_empty()
     }
 }
 
@@ -58,17 +168,45 @@ impl DepNode {
 impl DepNode {
     #[getter]
     fn dependencies(&self) -> PyResult<usize> {
-        println!("{}", self.dependencies.len());
         Ok(self.dependencies.len())
     }
+
+    #[getter]
+    fn edges(&self) -> HashMap<String, Vec<ImportEdge>> {
+        self.dependencies.clone()
+    }
+
+    #[getter]
+    fn category(&self) -> &'static str {
+        self.category.as_str()
+    }
 }
 
+/// An import statement for which no module could be found.
 #[pyclass]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
+pub struct UnresolvedImport {
+    // Absolute name which was looked up
+    #[pyo3(get)]
+    pub name: String,
+    // Module containing the import statement
+    #[pyo3(get)]
+    pub importer: String,
+    // The import as written in the source (ex: `..utils`)
+    #[pyo3(get)]
+    pub text: String,
+}
+
+#[pyclass]
+#[derive(Clone, Default)]
 pub struct DepGraph {
     pub nodes: HashMap<String, RefCell<DepNode>>,
-    root_nodes: HashSet<String>
-
+    root_nodes: HashSet<String>,
+    // Failures recorded while building, by the name of the module they occurred in
+    pub errors: Vec<(String, FastDepError)>,
+    pub unresolved: Vec<UnresolvedImport>,
+    // Entry points of the build, not carried over by `merge` / `clone_from`
+    pub roots: Vec<String>,
 }
 
 impl DepGraph {
@@ -76,33 +214,52 @@ impl DepGraph {
         DepGraph {
             nodes: HashMap::new(),
             root_nodes: HashSet::new(),
+            errors: vec![],
+            unresolved: vec![],
+            roots: vec![],
+        }
+    }
+
+    pub(crate) fn _check_node(&self, name: &str) -> Result<(), FastDepError> {
+        if !self.nodes.contains_key(name) {
+            return Err(FastDepError::graph(format!("Node does not exist on graph: {}", name)))
+        }
+        return Ok(())
+    }
+
+    pub fn add_error(&mut self, name: &str, err: FastDepError) {
+        debug!("Recording error for '{}': {}", name, err);
+        self.errors.push((name.to_string(), err));
+    }
+
+    pub fn add_unresolved(&mut self, unresolved: UnresolvedImport) {
+        if !self.unresolved.contains(&unresolved) {
+            self.unresolved.push(unresolved);
         }
     }
 
-    pub fn add_dependency(&self, from: &str, on: &str) {
+    pub fn add_dependency(&self, from: &str, on: &str, edge: ImportEdge) -> Result<(), FastDepError> {
         debug!("Add dependency '{}' -> '{}'", from, on);
 
         // Make sure we have the `on` node
-        assert!(
-            self.nodes.contains_key(from),
-            "Node does not exist on graph: {}", from
-        );
-        assert!(
-            self.nodes.contains_key(on),
-            "Node does not exist on graph: {}", on
-        );
+        self._check_node(from)?;
+        self._check_node(on)?;
 
         // TODO: Jonathon pls look at this
         let mut on_node = self.nodes.get(on).unwrap().borrow_mut();
         on_node.dependents.insert(from.to_string());
 
         let mut from_node = self.nodes.get(from).unwrap().borrow_mut();
-        from_node.dependencies.insert(on.to_string());
+        from_node.add_edge(on, edge);
 
         // Update depth relative to terminal node
-        assert!(!from_node.depth.is_none(), "Attempted to add dependency from node with uninitialized depth named: {}", from_node.name);
-
-        let current_depth = from_node.depth.unwrap() + 1 ;
+        let current_depth = match from_node.depth {
+            Some(depth) => depth + 1,
+            None => return Err(FastDepError::graph(format!(
+                "Attempted to add dependency from node with uninitialized depth named: {}",
+                from_node.name
+            )))
+        };
         if let Some(depth) = on_node.depth {
             if depth > current_depth {
                 debug!("Found shorter depth to '{}' new depth is {}", on_node.name, current_depth);
@@ -113,10 +270,14 @@ impl DepGraph {
             // If uninitialized, initialize 
             on_node.depth = Some(current_depth);
         }
+
+        return Ok(())
     }
 
-    pub fn add(&mut self, node: DepNode) -> Ref<DepNode> {
-        assert!(!self.nodes.contains_key(&node.name));
+    pub fn add(&mut self, node: DepNode) -> Result<Ref<'_, DepNode>, FastDepError> {
+        if self.nodes.contains_key(&node.name) {
+            return Err(FastDepError::graph(format!("Attempted to add node twice: {}", node.name)))
+        }
         debug!("Adding node to graph: {}", node.name);
 
         let name = node.name.clone(); // TODO: Better way?
@@ -127,27 +288,21 @@ impl DepGraph {
         );
         self.root_nodes.insert(name.clone());
 
-        self.nodes.get(&name).unwrap().borrow()
+        Ok(self.nodes.get(&name).unwrap().borrow())
     }
 
-    pub fn add_graph(&mut self, from: &str, on: &str, graph: DepGraph) {
+    pub fn add_graph(&mut self, from: &str, on: &str, edge: ImportEdge, graph: DepGraph) -> Result<(), FastDepError> {
         debug!(
             "Adding graph with {} nodes linked by {} -> {}",
             graph.nodes.len(),
             from,
             on
         );
-        assert!(
-            self.nodes.contains_key(from),
-            "Node does not exist on graph: {}", from
-        );
-        assert!(
-            graph.nodes.contains_key(on),
-            "Node does not exist on graph: {}", on
-        );
+        self._check_node(from)?;
+        graph._check_node(on)?;
 
         // Uninitialize depth from previous graph
-        for (_, node_cell) in &graph.nodes {
+        for node_cell in graph.nodes.values() {
             let mut node = node_cell.borrow_mut();
             node.depth = None;
         }
@@ -157,7 +312,13 @@ impl DepGraph {
         let on_node = graph.nodes.get(on).unwrap().borrow().clone();
 
         // Construct the depth from the 
-        let mut current_depth = from_node.depth.unwrap();
+        let mut current_depth = match from_node.depth {
+            Some(depth) => depth,
+            None => return Err(FastDepError::graph(format!(
+                "Attempted to add graph from node with uninitialized depth named: {}",
+                from_node.name
+            )))
+        };
         let mut to_process = vec![on_node.name.clone()];
         while let Some(name) = to_process.pop() {
             let mut node = graph.nodes.get(&name).unwrap().borrow_mut();
@@ -167,14 +328,14 @@ impl DepGraph {
                 node.depth = Some(current_depth);
 
                 // Make sure we process the dependencies of this node
-                to_process.extend(node.dependencies.iter().cloned());
+                to_process.extend(node.dependencies.keys().cloned());
                 current_depth += 1;
             }
         }
 
         // Finally merge graphs and add dependency on proper nodes
-        self.merge(graph);
-        self.add_dependency(from, on); // On will now be a part of the graph
+        self.merge(graph)?;
+        self.add_dependency(from, on, edge) // On will now be a part of the graph
     }
 
     // TODO: Read up on the `where` syntax
@@ -188,26 +349,28 @@ impl DepGraph {
         self.nodes.contains_key(name)
     }
 
-    pub fn clone_from(&self, name: &str) -> DepGraph {
-        assert!(self.has_node(name));
+    pub fn clone_from(&self, name: &str) -> Result<DepGraph, FastDepError> {
+        if !self.has_node(name) {
+            return Err(FastDepError::graph(format!("Attempted to clone from unknown node: {}", name)))
+        }
         let mut clone = DepGraph::new();
 
         let mut to_clone = BTreeSet::new();
         to_clone.insert(name.to_string());
         while let Some(name) = to_clone.pop_first() {
             let node = self.nodes.get(&name).unwrap().borrow();
-            clone.add(node.clone());
+            clone.add(node.clone())?;
 
             // Mark all dependencies which are a not yet in the cloned graph as needed to clone
-            for dep in &node.dependencies {
-                if !clone.has_node(&dep) {
+            for dep in node.dependencies.keys() {
+                if !clone.has_node(dep) {
                     to_clone.insert(dep.clone());
                 }
             }
         }
 
         // We now have a subset of nodes, remove any node `dependents` which were not copied over
-        for (_, node_cell) in &clone.nodes {
+        for node_cell in clone.nodes.values() {
             let mut node = node_cell.borrow_mut();
 
             // Remove non existent dependents
@@ -215,20 +378,43 @@ impl DepGraph {
             node.dependents.retain(|dep| clone.has_node(dep.as_str()));
         }
 
-        return clone
+        // Carry over failures and unresolved imports of the cloned modules
+        for (name, err) in &self.errors {
+            if clone.has_node(name) {
+                clone.errors.push((name.clone(), err.clone()));
+            }
+        }
+        for unresolved in &self.unresolved {
+            if clone.has_node(&unresolved.importer) {
+                clone.unresolved.push(unresolved.clone());
+            }
+        }
+
+        return Ok(clone)
     }
 
-    pub fn merge(&mut self, other: DepGraph) {
+    pub fn merge(&mut self, other: DepGraph) -> Result<(), FastDepError> {
+        for error in other.errors {
+            if !self.errors.contains(&error) {
+                self.errors.push(error);
+            }
+        }
+        for unresolved in other.unresolved {
+            self.add_unresolved(unresolved);
+        }
+
         for (name, node_cell) in other.nodes {
             if self.nodes.contains_key(&name) {
                 // Just update the data
                 let mut existing = self.nodes.get(&name).unwrap().borrow_mut();
-                existing.merge(node_cell.into_inner());
+                existing.merge(node_cell.into_inner())?;
             } else {
                 // Add the whole node
-                self.add(node_cell.into_inner());
+                self.add(node_cell.into_inner())?;
             }
         }
+
+        return Ok(())
     }
 }
 
@@ -241,8 +427,7 @@ impl DepGraph {
 
     pub fn num_dependencies(&self) -> usize {
         let mut acc = 0;
-        for (_, node_cell) in &self.nodes {
-            let node = node_cell.borrow();
+        for node_cell in self.nodes.values() {
             acc += node_cell.borrow().dependencies.len();
         }
 
@@ -251,14 +436,11 @@ impl DepGraph {
 
     pub fn keys(&self) -> HashSet<String> {
         // TODO: Probably expensive, faster to write a conversion for graph.keys() and Keys type?
-        self.nodes.iter().map(|(key, _)| key.to_string()).collect()
+        self.nodes.keys().map(|key| key.to_string()).collect()
     }
 
     pub fn get(&self, name: &str) -> PyResult<DepNode> {
-        assert!(
-            self.nodes.contains_key(name),
-            "Node does not exist on graph: {}", name
-        );
+        self._check_node(name)?;
         let node = self.nodes.get(name).unwrap().borrow();
 
         return Ok( node.clone() )
@@ -267,7 +449,7 @@ impl DepGraph {
     pub fn get_all_scoped(&self, scope: &str) -> PyResult<Vec<DepNode>> {
         let mut nodes = vec![];
 
-        for (_, node_cell) in &self.nodes {
+        for node_cell in self.nodes.values() {
             let node = node_cell.borrow();
             if node.name.starts_with(scope) {
                 nodes.push(node.clone());
@@ -280,7 +462,7 @@ impl DepGraph {
     pub fn origins(&mut self) -> Vec<String> {
         let mut origins = vec![];
 
-        for (_, node_cell) in &self.nodes {
+        for node_cell in self.nodes.values() {
             let node = node_cell.borrow_mut();
             if let Some(origin) = &node.spec.origin {
                 origins.push(origin.clone());
@@ -290,10 +472,148 @@ impl DepGraph {
         return origins
     }
 
+    #[pyo3(name = "unresolved")]
+    pub fn py_unresolved(&self) -> Vec<UnresolvedImport> {
+        self.unresolved.clone()
+    }
+
+    #[pyo3(name = "to_json")]
+    pub fn py_to_json(&self, pretty: Option<bool>) -> PyResult<String> {
+        Ok(self.to_json(pretty.unwrap_or(false))?)
+    }
+
+    #[staticmethod]
+    #[pyo3(name = "from_json")]
+    pub fn py_from_json(data: &str) -> PyResult<DepGraph> {
+        Ok(DepGraph::from_json(data)?)
+    }
+
+    #[pyo3(name = "to_dot")]
+    pub fn py_to_dot(&self, max_depth: Option<i32>, collapse_packages: Option<bool>, color: Option<bool>) -> String {
+        self.to_dot(&ExportOptions {
+            max_depth: max_depth,
+            collapse_packages: collapse_packages.unwrap_or(false),
+            color: color.unwrap_or(false),
+        })
+    }
+
+    #[pyo3(name = "to_mermaid")]
+    pub fn py_to_mermaid(&self, max_depth: Option<i32>, collapse_packages: Option<bool>, color: Option<bool>) -> String {
+        self.to_mermaid(&ExportOptions {
+            max_depth: max_depth,
+            collapse_packages: collapse_packages.unwrap_or(false),
+            color: color.unwrap_or(false),
+        })
+    }
+
+    #[pyo3(name = "to_tree")]
+    pub fn py_to_tree(&self, max_depth: Option<usize>) -> String {
+        self.to_tree(max_depth)
+    }
+
+    #[pyo3(name = "roots")]
+    pub fn py_roots(&self) -> Vec<String> {
+        self.roots.clone()
+    }
+
+    #[pyo3(name = "by_category")]
+    pub fn py_by_category(&self, category: &str) -> PyResult<Vec<String>> {
+        Ok(self.by_category(parse_category(category)?))
+    }
+
+    #[pyo3(name = "filter_categories")]
+    pub fn py_filter_categories(&self, categories: Vec<String>) -> PyResult<DepGraph> {
+        let categories = categories.iter().map(|category| parse_category(category)).collect::<PyResult<Vec<_>>>()?;
+        Ok(self.filter_categories(&categories)?)
+    }
+
+    #[pyo3(name = "distributions")]
+    pub fn py_distributions(&self) -> Vec<importlib::Distribution> {
+        self.distributions()
+    }
+
+    #[pyo3(name = "check_dependencies")]
+    pub fn py_check_dependencies(&self, pyproject: &str, requirements: Option<Vec<String>>) -> PyResult<DependencyReport> {
+        let mut declared = DeclaredDependencies::from_pyproject(std::path::Path::new(pyproject))?;
+        for path in requirements.unwrap_or_default() {
+            declared.add_requirements(std::path::Path::new(&path))?;
+        }
+        Ok(self.check_dependencies(&declared))
+    }
+
+    #[pyo3(name = "diff")]
+    pub fn py_diff(&self, other: &DepGraph) -> GraphDiff {
+        self.diff(other)
+    }
+
+    #[pyo3(name = "modules_for_files")]
+    pub fn py_modules_for_files(&self, files: Vec<String>) -> Vec<String> {
+        self.modules_for_files(&files)
+    }
+
+    #[pyo3(name = "affected_modules")]
+    pub fn py_affected_modules(&self, modules: Vec<String>) -> HashSet<String> {
+        self.affected_modules(&modules)
+    }
+
+    #[pyo3(name = "affected_tests")]
+    pub fn py_affected_tests(&self, files: Vec<String>, patterns: Option<Vec<String>>) -> Vec<String> {
+        let patterns = patterns.unwrap_or_else(|| DEFAULT_TEST_PATTERNS.iter().map(|pattern| pattern.to_string()).collect());
+        self.affected_tests(&files, &patterns)
+    }
+
+    #[pyo3(name = "check_contracts")]
+    pub fn py_check_contracts(&self, path: &str) -> PyResult<Vec<ContractViolation>> {
+        let contracts = load_contracts(std::path::Path::new(path))?;
+        Ok(self.check_contracts(&contracts))
+    }
+
+    #[pyo3(name = "orphans")]
+    pub fn py_orphans(&self) -> Vec<String> {
+        self.orphans()
+    }
+
+    #[pyo3(name = "depths_from")]
+    pub fn py_depths_from(&self, root: &str) -> PyResult<HashMap<String, usize>> {
+        Ok(self.depths_from(root)?)
+    }
+
+    #[pyo3(name = "reachable_from")]
+    pub fn py_reachable_from(&self, root: &str) -> PyResult<HashSet<String>> {
+        Ok(self.depths_from(root)?.into_keys().collect())
+    }
+
+    #[pyo3(name = "path")]
+    pub fn py_path(&self, from_: &str, to: &str) -> PyResult<Option<Vec<String>>> {
+        Ok(self.path(from_, to)?)
+    }
+
+    #[pyo3(name = "all_paths")]
+    pub fn py_all_paths(&self, from_: &str, to: &str, limit: Option<usize>) -> PyResult<Vec<Vec<String>>> {
+        Ok(self.all_paths(from_, to, limit.unwrap_or(10))?)
+    }
+
+    #[pyo3(name = "cycles")]
+    pub fn py_cycles(&self, shortest: Option<bool>) -> Vec<ImportCycle> {
+        self.cycles(shortest.unwrap_or(false))
+    }
+
+    #[pyo3(name = "strongly_connected_components")]
+    pub fn py_strongly_connected_components(&self) -> Vec<Vec<String>> {
+        self.strongly_connected_components()
+    }
+
+    #[pyo3(name = "errors")]
+    pub fn py_errors(&self, py: Python<'_>) -> Vec<(String, PyObject)> {
+        self.errors.iter().map(|(name, err)| {
+            (name.clone(), err.to_pyerr().into_value(py).into())
+        }).collect()
+    }
+
     pub fn names(&mut self) -> Vec<String> {
         let mut names = vec![];
 
-        for (_, node_cell) in &self.nodes {
+        for node_cell in self.nodes.values() {
             let node = node_cell.borrow_mut();
             names.push(node.name.clone());
         }
@@ -0,0 +1,91 @@
+use std::fmt;
+use std::io;
+
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Python side hierarchy, everything derives from `fast_dep.FastDepError`
+pub mod exceptions {
+    use pyo3::create_exception;
+    use pyo3::exceptions::PyException;
+
+    create_exception!(fast_dep, FastDepError, PyException);
+    create_exception!(fast_dep, ParseError, FastDepError);
+    create_exception!(fast_dep, ResolutionError, FastDepError);
+    create_exception!(fast_dep, LoadError, FastDepError);
+    create_exception!(fast_dep, GraphError, FastDepError);
+    create_exception!(fast_dep, SerializationError, FastDepError);
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FastDepError {
+    // Source could not be parsed by the minimal parser
+    Parse {
+        origin: Option<String>,
+        message: String
+    },
+    // A (relative) import name could not be turned into an absolute module name
+    Resolution {
+        name: String,
+        message: String
+    },
+    // Module source could not be read
+    Io {
+        path: String,
+        message: String
+    },
+    // Internal invariants of `DepGraph` were violated
+    Graph {
+        message: String
+    },
+    // A stored graph could not be written or read back
+    Serialization {
+        message: String
+    },
+}
+
+impl FastDepError {
+    pub fn graph(message: String) -> FastDepError {
+        FastDepError::Graph { message: message }
+    }
+
+    pub fn io(path: &str, err: io::Error) -> FastDepError {
+        FastDepError::Io {
+            path: path.to_string(),
+            message: err.to_string()
+        }
+    }
+
+    pub fn to_pyerr(&self) -> PyErr {
+        let message = self.to_string();
+        match self {
+            FastDepError::Parse { .. } => exceptions::ParseError::new_err(message),
+            FastDepError::Resolution { .. } => exceptions::ResolutionError::new_err(message),
+            FastDepError::Io { .. } => exceptions::LoadError::new_err(message),
+            FastDepError::Graph { .. } => exceptions::GraphError::new_err(message),
+            FastDepError::Serialization { .. } => exceptions::SerializationError::new_err(message),
+        }
+    }
+}
+
+impl fmt::Display for FastDepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FastDepError::Parse { origin: Some(origin), message } => write!(f, "Could not parse '{}': {}", origin, message),
+            FastDepError::Parse { origin: None, message } => write!(f, "Could not parse source: {}", message),
+            FastDepError::Resolution { name, message } => write!(f, "Could not resolve '{}': {}", name, message),
+            FastDepError::Io { path, message } => write!(f, "Could not read '{}': {}", path, message),
+            FastDepError::Graph { message } => write!(f, "{}", message),
+            FastDepError::Serialization { message } => write!(f, "Invalid graph document: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for FastDepError {}
+
+impl From<FastDepError> for PyErr {
+    fn from(err: FastDepError) -> PyErr {
+        err.to_pyerr()
+    }
+}
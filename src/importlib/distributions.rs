@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use log::{debug};
+
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::types::*;
+
+/// An installed distribution (the name it is published under on PyPI).
+#[pyclass]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Distribution {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub version: String,
+}
+
+#[pymethods]
+impl Distribution {
+    fn __repr__(&self) -> String {
+        format!("<Distribution {}=={}>", self.name, self.version)
+    }
+}
+
+/// Distributions installed in the search path entries, from their `*.dist-info` / `*.egg-info` metadata.
+///
+/// Modules are mapped by the files listed in `RECORD`, `top_level.txt` covers distributions without one.
+#[derive(Default)]
+pub struct DistributionIndex {
+    distributions: Vec<Distribution>,
+    by_file: HashMap<PathBuf, usize>,
+    by_top_level: HashMap<String, usize>,
+}
+
+// First field of a `RECORD` line, which is CSV: fields with a comma or a quote are quoted and quotes in them doubled
+fn record_path(line: &str) -> String {
+    let Some(quoted) = line.strip_prefix('"') else {
+        return line.split(',').next().unwrap_or_default().to_string()
+    };
+
+    let mut path = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c == '"' {
+            match chars.next() {
+                Some('"') => path.push('"'),
+                _ => break
+            }
+        } else {
+            path.push(c);
+        }
+    }
+    path
+}
+
+// `Name` and `Version` headers of `METADATA` / `PKG-INFO`
+fn read_metadata(path: &Path) -> Option<Distribution> {
+    let metadata = fs::read_to_string(path).ok()?;
+    let mut name = None;
+    let mut version = None;
+    for line in metadata.lines() {
+        // Headers end at the first empty line, the description follows
+        if line.is_empty() {
+            break
+        }
+        if let Some(value) = line.strip_prefix("Name:") {
+            name = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("Version:") {
+            version = Some(value.trim().to_string());
+        }
+    }
+
+    Some(Distribution {
+        name: name?,
+        version: version?,
+    })
+}
+
+impl DistributionIndex {
+    pub fn scan(search_paths: &[String]) -> DistributionIndex {
+        let mut index = DistributionIndex::default();
+
+        for entry in search_paths {
+            let Ok(dir_entries) = fs::read_dir(entry) else {
+                continue
+            };
+            let mut info_dirs: Vec<PathBuf> = dir_entries
+                .filter_map(|dir_entry| dir_entry.ok())
+                .map(|dir_entry| dir_entry.path())
+                .filter(|path| {
+                    let name = path.to_string_lossy();
+                    path.is_dir() && (name.ends_with(".dist-info") || name.ends_with(".egg-info"))
+                })
+                .collect();
+            info_dirs.sort();
+
+            for info_dir in info_dirs {
+                index._add(Path::new(entry), &info_dir);
+            }
+        }
+
+        debug!("Found {} installed distributions", index.distributions.len());
+        index
+    }
+
+    fn _add(&mut self, site_dir: &Path, info_dir: &Path) {
+        let metadata = if info_dir.extension().is_some_and(|extension| extension == "dist-info") {
+            info_dir.join("METADATA")
+        } else {
+            info_dir.join("PKG-INFO")
+        };
+        let Some(distribution) = read_metadata(&metadata) else {
+            return
+        };
+
+        let id = self.distributions.len();
+        self.distributions.push(distribution);
+
+        // `path,hash,size`, paths are relative to the site directory
+        if let Ok(record) = fs::read_to_string(info_dir.join("RECORD")) {
+            for line in record.lines() {
+                let path = record_path(line);
+                // Scripts and data files installed outside of the site directory
+                if path.is_empty() || Path::new(&path).components().any(|part| part == Component::ParentDir) {
+                    continue;
+                }
+                self.by_file.entry(site_dir.join(&path)).or_insert(id);
+            }
+        }
+
+        if let Ok(top_level) = fs::read_to_string(info_dir.join("top_level.txt")) {
+            for name in top_level.lines().map(|name| name.trim()).filter(|name| !name.is_empty()) {
+                self.by_top_level.entry(name.to_string()).or_insert(id);
+            }
+        }
+    }
+
+    /// Distribution which installed the file of `spec`, falling back to `top_level.txt` when `by_top_level` is set.
+    pub fn for_spec(&self, spec: &ModuleSpec, by_top_level: bool) -> Option<&Distribution> {
+        if let Some(origin) = &spec.origin {
+            if let Some(id) = self.by_file.get(Path::new(origin)) {
+                return Some(&self.distributions[*id])
+            }
+        }
+
+        if !by_top_level {
+            return None
+        }
+        let top_level = spec.name.split('.').next().unwrap();
+        self.by_top_level.get(top_level).map(|id| &self.distributions[*id])
+    }
+}
@@ -2,4 +2,10 @@ pub mod types;
 pub use types::*;
 
 pub mod util;
-pub use util::*;
\ No newline at end of file
+pub use util::*;
+
+pub mod resolver;
+pub use resolver::*;
+
+pub mod distributions;
+pub use distributions::*;
@@ -0,0 +1,200 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+use log::{debug};
+
+use super::distributions::*;
+use super::types::*;
+
+// Defaults matching CPython's `importlib.machinery` on POSIX platforms
+const DEFAULT_EXTENSION_SUFFIXES: [&str; 2] = [".abi3.so", ".so"];
+const DEFAULT_SOURCE_SUFFIXES: [&str; 1] = [".py"];
+const DEFAULT_BYTECODE_SUFFIXES: [&str; 1] = [".pyc"];
+
+/// Native replacement for `importlib.util.find_spec`.
+///
+/// Mirrors the lookup order of the default `sys.meta_path` (built-in, frozen, then path based
+/// finders) without calling into the interpreter. Interpreter state which cannot be derived from
+/// the file system (built-in / frozen names, modules aliased in `sys.modules`) is configured up
+/// front, see `InterpreterConfig`.
+pub struct Resolver {
+    pub search_paths: Vec<String>,
+    pub builtin_modules: HashSet<String>,
+    pub frozen_modules: HashSet<String>,
+    // Names which are bound to a differently named module in `sys.modules` (ex: `os.path` -> `posixpath`)
+    pub aliases: HashMap<String, String>,
+    pub extension_suffixes: Vec<String>,
+    pub source_suffixes: Vec<String>,
+    pub bytecode_suffixes: Vec<String>,
+    cache: HashMap<String, Option<ModuleSpec>>,
+    // Specs which are not found on the search path (ex: scanned project modules), never persisted
+    overrides: HashMap<String, ModuleSpec>,
+    // Read on first use, most builds never need it
+    distributions: Option<DistributionIndex>,
+}
+
+impl Resolver {
+    pub fn new(search_paths: Vec<String>) -> Resolver {
+        Resolver {
+            search_paths: search_paths,
+            builtin_modules: HashSet::new(),
+            frozen_modules: HashSet::new(),
+            aliases: HashMap::new(),
+            extension_suffixes: DEFAULT_EXTENSION_SUFFIXES.iter().map(|s| s.to_string()).collect(),
+            source_suffixes: DEFAULT_SOURCE_SUFFIXES.iter().map(|s| s.to_string()).collect(),
+            bytecode_suffixes: DEFAULT_BYTECODE_SUFFIXES.iter().map(|s| s.to_string()).collect(),
+            cache: HashMap::new(),
+            overrides: HashMap::new(),
+            distributions: None,
+        }
+    }
+
+    pub fn find_spec(&mut self, name: &str) -> Option<ModuleSpec> {
+        if let Some(spec) = self.overrides.get(name) {
+            return Some(spec.clone())
+        }
+        if let Some(spec) = self.cache.get(name) {
+            return spec.clone()
+        }
+
+        let spec = self._find_spec(name);
+        if let Some(spec) = &spec {
+            debug!("Resolved spec: {} {:?}", spec.name, spec.origin);
+        }
+        self.cache.insert(name.to_string(), spec.clone());
+
+        return spec
+    }
+
+    // Specs found by earlier lookups, used to persist them between processes. Overridden modules and their
+    // submodules depend on the overrides and are left out
+    pub fn resolved(&self) -> impl Iterator<Item = (&String, &ModuleSpec)> {
+        self.cache.iter()
+            .filter(|(name, _)| !self._is_overridden(name))
+            .filter_map(|(name, spec)| spec.as_ref().map(|spec| (name, spec)))
+    }
+
+    /// Installed distribution providing the module of `spec`, see `DistributionIndex::for_spec`.
+    pub fn distribution(&mut self, spec: &ModuleSpec, by_top_level: bool) -> Option<Distribution> {
+        let search_paths = &self.search_paths;
+        let index = self.distributions.get_or_insert_with(|| DistributionIndex::scan(search_paths));
+        index.for_spec(spec, by_top_level).cloned()
+    }
+
+    // Seed a lookup result, later calls to `find_spec` for this name return it without searching
+    pub fn insert_spec(&mut self, name: &str, spec: ModuleSpec) {
+        self.cache.insert(name.to_string(), Some(spec));
+    }
+
+    // Take `spec` for `name` over the search path, unlike `insert_spec` it is not returned by `resolved`
+    pub fn override_spec(&mut self, name: &str, spec: ModuleSpec) {
+        self.overrides.insert(name.to_string(), spec);
+    }
+
+    fn _is_overridden(&self, name: &str) -> bool {
+        if self.overrides.is_empty() {
+            return false
+        }
+        let mut end = name.len();
+        loop {
+            if self.overrides.contains_key(&name[..end]) {
+                return true
+            }
+            match name[..end].rfind('.') {
+                Some(dot) => end = dot,
+                None => return false
+            }
+        }
+    }
+
+    fn _find_spec(&mut self, name: &str) -> Option<ModuleSpec> {
+        if name.is_empty() {
+            return None
+        }
+
+        if let Some(alias) = self.aliases.get(name).cloned() {
+            return self.find_spec(&alias)
+        }
+
+        if self.builtin_modules.contains(name) {
+            return Some(ModuleSpec::new(name, Some("built-in".to_string()), None))
+        }
+        if self.frozen_modules.contains(name) {
+            return Some(ModuleSpec::new(name, Some("frozen".to_string()), None))
+        }
+
+        // Submodules are searched for in the locations of their parent package only
+        let search_paths = match name.rsplit_once('.') {
+            Some((parent, _)) => {
+                let parent_spec = self.find_spec(parent)?;
+                parent_spec.submodule_search_locations?
+            },
+            None => self.search_paths.clone()
+        };
+
+        let tail = name.rsplit('.').next().unwrap();
+        let mut namespace_portions = vec![];
+        for entry in &search_paths {
+            let entry = if entry.is_empty() {
+                // Like `PathFinder`, the entry is skipped when the working directory can't be read (ex: deleted)
+                match env::current_dir() {
+                    Ok(cwd) => cwd,
+                    Err(_) => continue
+                }
+            } else {
+                PathBuf::from(entry)
+            };
+            if !entry.is_dir() {
+                continue;
+            }
+
+            // Regular package
+            let package_dir = entry.join(tail);
+            if package_dir.is_dir() {
+                if let Some(init) = self._find_file(&package_dir, "__init__") {
+                    return Some(ModuleSpec::new(
+                        name,
+                        Some(path_to_string(&init)),
+                        Some(vec![path_to_string(&package_dir)])
+                    ))
+                }
+            }
+
+            // Single file module
+            if let Some(file) = self._find_file(&entry, tail) {
+                return Some(ModuleSpec::new(name, Some(path_to_string(&file)), None))
+            }
+
+            // Directories without `__init__` may contribute to a namespace package (PEP 420)
+            if package_dir.is_dir() {
+                namespace_portions.push(path_to_string(&package_dir));
+            }
+        }
+
+        if !namespace_portions.is_empty() {
+            return Some(ModuleSpec::new(name, None, Some(namespace_portions)))
+        }
+
+        return None
+    }
+
+    // Same suffix precedence as `FileFinder`: extensions, then source, then bytecode
+    fn _find_file(&self, dir: &Path, stem: &str) -> Option<PathBuf> {
+        let suffixes = self.extension_suffixes.iter()
+            .chain(self.source_suffixes.iter())
+            .chain(self.bytecode_suffixes.iter());
+
+        for suffix in suffixes {
+            let path = dir.join(format!("{}{}", stem, suffix));
+            if path.is_file() {
+                return Some(path)
+            }
+        }
+
+        return None
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
@@ -1,8 +1,9 @@
 use pyo3::prelude::*;
 use pyo3::types::{PyDict};
+use serde::{Deserialize, Serialize};
 
 #[derive(FromPyObject)]
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ModuleSpec {
     pub name: String,
     pub origin: Option<String>,
@@ -11,12 +12,28 @@ pub struct ModuleSpec {
 }
 
 impl ModuleSpec {
-    pub fn is_package(&self) -> bool {
-        match self.submodule_search_locations {
-            Some(_) => true,
-            None => false
+    pub fn new(name: &str, origin: Option<String>, submodule_search_locations: Option<Vec<String>>) -> ModuleSpec {
+        // Same as `ModuleSpec.parent`, packages are their own parent
+        let parent = if submodule_search_locations.is_some() {
+            name.to_string()
+        } else {
+            match name.rsplit_once('.') {
+                Some((parent, _)) => parent.to_string(),
+                None => "".to_string()
+            }
+        };
+
+        ModuleSpec {
+            name: name.to_string(),
+            origin: origin,
+            parent: parent,
+            submodule_search_locations: submodule_search_locations
         }
     }
+
+    pub fn is_package(&self) -> bool {
+        self.submodule_search_locations.is_some()
+    }
 }
 
 impl IntoPy<PyObject> for ModuleSpec {
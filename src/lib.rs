@@ -1,16 +1,12 @@
 use pyo3::prelude::*;
 
-use std::path::Path;
-use std::fs::File;
-use std::io::prelude::*;
-use std::error::Error;
-
 // TODO: Do tests actually need this?
 pub mod minimal_parser;
 pub use minimal_parser::*;
 
-mod core;
-mod importlib;
+pub mod core;
+pub mod importlib;
+pub mod error;
 
 #[pymodule]
 fn fast_dep(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
@@ -19,10 +15,31 @@ fn fast_dep(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<core::DepNode>()?;
     m.add_class::<core::DepGraph>()?;
     m.add_class::<core::GraphBuilder>()?;
+    m.add_class::<core::UnresolvedImport>()?;
+    m.add_class::<core::ImportEdge>()?;
+    m.add_class::<core::ImportCycle>()?;
+    m.add_class::<importlib::Distribution>()?;
+    m.add_class::<core::UndeclaredImport>()?;
+    m.add_class::<core::DependencyReport>()?;
+    m.add_class::<core::GraphDiff>()?;
+    m.add_class::<core::DepthChange>()?;
+    m.add_class::<core::ContractViolation>()?;
+
+    m.add("FastDepError", _py.get_type::<error::exceptions::FastDepError>())?;
+    m.add("ParseError", _py.get_type::<error::exceptions::ParseError>())?;
+    m.add("ResolutionError", _py.get_type::<error::exceptions::ResolutionError>())?;
+    m.add("LoadError", _py.get_type::<error::exceptions::LoadError>())?;
+    m.add("GraphError", _py.get_type::<error::exceptions::GraphError>())?;
+    m.add("SerializationError", _py.get_type::<error::exceptions::SerializationError>())?;
 
     let parser_module = PyModule::new(_py, "parser")?;
     parser_module.add_function(wrap_pyfunction!(parse, parser_module)?)?;
 
     m.add_submodule(parser_module)?;
+
+    let resolver_module = PyModule::new(_py, "resolver")?;
+    resolver_module.add_function(wrap_pyfunction!(core::py_find_spec, resolver_module)?)?;
+
+    m.add_submodule(resolver_module)?;
     Ok(())
 }
\ No newline at end of file
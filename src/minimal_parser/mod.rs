@@ -4,5 +4,8 @@ pub use types::*;
 pub mod parser;
 pub use parser::*;
 
+pub mod scope;
+pub use scope::*;
+
 pub mod util;
 pub use util::*;
\ No newline at end of file
@@ -4,7 +4,9 @@ use pest::Parser;
 use pest::iterators::Pair;
 use pest_derive::Parser;
 
+use crate::error::FastDepError;
 use super::types::*;
+use super::scope::*;
 
 #[derive(Parser)]
 #[grammar = "minimal_parser/grammar.pest"]
@@ -12,13 +14,28 @@ struct PESTParser;
 
 
 #[pyfunction]
-pub fn parse(source: &str) -> Vec<ImportStmt> {
-    let pairs = PESTParser::parse(Rule::python, source).expect("Could not parse file");
+pub fn parse(source: &str) -> Result<Vec<ImportStmt>, FastDepError> {
+    parse_source(source).map(|parsed| parsed.imports)
+}
+
+/// Imports and literal `__all__` of a module.
+///
+/// Assignments to `__all__` replace the names and augmented assignments extend them, in the order they appear.
+pub fn parse_source(source: &str) -> Result<ParsedSource, FastDepError> {
+    let pairs = PESTParser::parse(Rule::python, source).map_err(|err| FastDepError::Parse {
+        origin: None,
+        message: err.to_string()
+    })?;
 
     let mut stmts: Vec<ImportStmt> = Vec::new();
+    let mut all: Option<Vec<String>> = None;
+    let scopes = ScopeTracker::new(source);
     for pair in pairs.flatten() {
         match pair.as_rule() {
             Rule::import => {
+                let (line, column) = pair.line_col();
+                let context = scopes.context(line);
+
                 // Pull the first inner value and validate it is a alias_list
                 let alias_list = pair.into_inner().next().unwrap();
                 assert_eq!(Rule::alias_list, alias_list.as_rule());
@@ -29,9 +46,16 @@ pub fn parse(source: &str) -> Vec<ImportStmt> {
                     names.push(parse_alias(alias))
                 }
 
-                stmts.push(ImportStmt::Import { names: names });
+                stmts.push(ImportStmt::Import {
+                    names: names,
+                    line: line,
+                    column: column,
+                    context: context
+                });
             },
             Rule::import_from => {
+                let (line, column) = pair.line_col();
+                let context = scopes.context(line);
                 let mut inner = pair.into_inner();
 
                 let module_spec = inner.next().unwrap();
@@ -44,13 +68,21 @@ pub fn parse(source: &str) -> Vec<ImportStmt> {
                 let list_inner = from_alias_list.into_inner();
                 for list_element in list_inner {
                     match list_element.as_rule() {
+                        Rule::star_alias => {
+                            let (line, column) = list_element.line_col();
+                            names.push(Alias {
+                                name: "*".to_string(),
+                                asname: None,
+                                line: line,
+                                column: column
+                            })
+                        },
                         Rule::alias => names.push(parse_alias(list_element)),
                         Rule::additional => {
                             // Many fields in additional, just pull out aliases
                             for additional_element in list_element.into_inner() {
-                                match additional_element.as_rule() {
-                                    Rule::alias => names.push(parse_alias(additional_element)),
-                                    _ => ()
+                                if additional_element.as_rule() == Rule::alias {
+                                    names.push(parse_alias(additional_element))
                                 }
                             }
                         },
@@ -66,31 +98,90 @@ pub fn parse(source: &str) -> Vec<ImportStmt> {
                 stmts.push( ImportStmt::ImportFrom {
                     module: Some(module),
                     names: names,
-                    level: Some(level)
+                    level: Some(level),
+                    line: line,
+                    column: column,
+                    context: context
                 })
-            }
+            },
+            Rule::dynamic_import => {
+                let (line, column) = pair.line_col();
+                let context = scopes.context(line);
+
+                // Either `import_module_call` or `dynamic_call`
+                let call = pair.into_inner().next().unwrap();
+                let mut inner = call.into_inner();
+                let function = inner.next().unwrap().as_str().to_string();
+                let name = unquote(inner.next().unwrap().as_str());
+                let package = inner.next().map(|pair| unquote(pair.as_str()));
+
+                // `pkgutil.resolve_name("pkg.mod:attr")` imports `pkg.mod`
+                let name = match name.split_once(':') {
+                    Some((module, _)) => module.to_string(),
+                    None => name
+                };
+
+                stmts.push(ImportStmt::Dynamic {
+                    function: function,
+                    name: name,
+                    package: package,
+                    line: line,
+                    column: column,
+                    context: context
+                })
+            },
+            Rule::dunder_all => {
+                let (line, _) = pair.line_col();
+                if scopes.context(line).scope != ImportScope::Module {
+                    continue;
+                }
+
+                let mut inner = pair.into_inner();
+                let operator = inner.next().unwrap();
+                assert_eq!(Rule::all_operator, operator.as_rule());
+                let all_names = inner.next().unwrap();
+                assert_eq!(Rule::all_names, all_names.as_rule());
+
+                let names = all_names.into_inner()
+                    .flat_map(|list| list.into_inner())
+                    .filter(|name| name.as_rule() == Rule::all_name)
+                    .map(|name| unquote(name.as_str()));
+                if operator.as_str() == "+=" {
+                    all.get_or_insert_with(Vec::new).extend(names);
+                } else {
+                    all = Some(names.collect());
+                }
+            },
             _ => ()
         }
     }
 
-    return stmts
+    return Ok(ParsedSource {
+        imports: stmts,
+        dunder_all: all
+    })
+}
+
+// Contents of a single line string literal
+fn unquote(quoted: &str) -> String {
+    quoted[1..quoted.len() - 1].to_string()
 }
 
 fn parse_alias(alias: Pair<Rule>) -> Alias {
     assert_eq!(Rule::alias, alias.as_rule());
+    let (line, column) = alias.line_col();
 
     let mut contents = alias.into_inner();
     // Should always have a name
     let qualified_name = contents.next().unwrap().as_str();
     // May have a "asname"
-    let asname = match contents.next() {
-        Some(pair) => Some(pair.as_str().to_string()),
-        None => None
-    };
+    let asname = contents.next().map(|pair| pair.as_str().to_string());
 
     Alias {
         name: qualified_name.to_string(),
-        asname: asname
+        asname: asname,
+        line: line,
+        column: column
     }
 }
 
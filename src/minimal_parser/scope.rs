@@ -0,0 +1,294 @@
+/*
+The grammar is line based and has no notion of blocks, the following recovers enough of the block structure from indentation to tell where an import statement lives.
+
+Only physical lines which start a logical line are considered. Lines continued through open brackets, triple quoted strings or trailing backslashes do not open or close blocks.
+*/
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportScope {
+    Module,
+    Class,
+    // Any enclosing `def`, these imports only run when the function is called
+    Function,
+}
+
+impl ImportScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportScope::Module => "module",
+            ImportScope::Class => "class",
+            ImportScope::Function => "function",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportContext {
+    pub scope: ImportScope,
+    // Under an `if` / `try` / `with` / loop / `match` block
+    pub conditional: bool,
+    // Only executed by type checkers, under `if TYPE_CHECKING:` (or the `else` of `if not TYPE_CHECKING:`)
+    pub type_checking: bool,
+}
+
+impl ImportContext {
+    // Executed when the module itself is imported
+    pub fn is_eager(&self) -> bool {
+        self.scope != ImportScope::Function
+    }
+}
+
+impl Default for ImportContext {
+    fn default() -> Self {
+        ImportContext {
+            scope: ImportScope::Module,
+            conditional: false,
+            type_checking: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BlockKind {
+    Function,
+    Class,
+    Conditional,
+}
+
+const CONDITIONAL_KEYWORDS: [&str; 10] = [
+    "if", "elif", "else", "try", "except", "finally", "with", "for", "while", "async"
+];
+// Soft keywords, only headers when the line ends the statement with a colon
+const SOFT_KEYWORDS: [&str; 2] = ["match", "case"];
+
+fn block_kind(text: &str) -> Option<BlockKind> {
+    let keyword: String = text.chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    let rest = &text[keyword.len()..];
+
+    // Keywords must be followed by something which is not part of an identifier
+    if !rest.is_empty() && !rest.starts_with([' ', '\t', ':', '(', '[', '\\', '"', '\'']) {
+        return None
+    }
+
+    match keyword.as_str() {
+        "def" => Some(BlockKind::Function),
+        "class" => Some(BlockKind::Class),
+        "async" => {
+            // `async def` is a function, `async with` / `async for` are conditional
+            if rest.trim_start().starts_with("def") {
+                Some(BlockKind::Function)
+            } else {
+                Some(BlockKind::Conditional)
+            }
+        },
+        k if CONDITIONAL_KEYWORDS.contains(&k) => Some(BlockKind::Conditional),
+        k if SOFT_KEYWORDS.contains(&k) => {
+            let code = rest.split('#').next().unwrap().trim_end();
+            if code.ends_with(':') {
+                Some(BlockKind::Conditional)
+            } else {
+                None
+            }
+        },
+        _ => None
+    }
+}
+
+const TYPE_CHECKING_NAMES: [&str; 2] = ["TYPE_CHECKING", "typing.TYPE_CHECKING"];
+
+// For `if` / `elif` headers testing `TYPE_CHECKING` returns whether the test holds for type
+// checkers (`if TYPE_CHECKING:`) or for the runtime (`if not TYPE_CHECKING:`)
+fn type_checking_test(text: &str) -> Option<bool> {
+    let condition = text.strip_prefix("if ")
+        .or_else(|| text.strip_prefix("elif "))?;
+    let condition = condition.split('#').next().unwrap().trim_end().strip_suffix(':')?.trim();
+
+    if TYPE_CHECKING_NAMES.contains(&condition) {
+        return Some(true)
+    }
+    if let Some(negated) = condition.strip_prefix("not ") {
+        if TYPE_CHECKING_NAMES.contains(&negated.trim()) {
+            return Some(false)
+        }
+    }
+
+    None
+}
+
+fn is_branch(text: &str) -> bool {
+    let keyword = text.split([' ', ':', '(']).next().unwrap();
+    keyword == "else" || keyword == "elif"
+}
+
+// Visual width of leading indentation, tabs advance to the next multiple of 8 like the tokenizer
+fn indent_width(line: &str) -> usize {
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width = (width / 8 + 1) * 8,
+            _ => break
+        }
+    }
+
+    width
+}
+
+#[derive(Default)]
+struct Continuation {
+    depth: i32,
+    triple_quote: Option<&'static str>,
+    backslash: bool,
+}
+
+impl Continuation {
+    fn active(&self) -> bool {
+        self.depth > 0 || self.triple_quote.is_some() || self.backslash
+    }
+
+    fn scan(&mut self, text: &str) {
+        let bytes = text.as_bytes();
+        let mut i = 0;
+        self.backslash = false;
+
+        while i < bytes.len() {
+            if let Some(quote) = self.triple_quote {
+                match text[i..].find(quote) {
+                    Some(end) => {
+                        i += end + 3;
+                        self.triple_quote = None;
+                        continue;
+                    },
+                    None => return
+                }
+            }
+
+            match bytes[i] {
+                b'#' => return,
+                b'(' | b'[' | b'{' => self.depth += 1,
+                b')' | b']' | b'}' => self.depth = (self.depth - 1).max(0),
+                b'"' | b'\'' => {
+                    if text[i..].starts_with("\"\"\"") {
+                        self.triple_quote = Some("\"\"\"");
+                        i += 3;
+                        continue;
+                    }
+                    if text[i..].starts_with("'''") {
+                        self.triple_quote = Some("'''");
+                        i += 3;
+                        continue;
+                    }
+
+                    // Single line string, skip to the closing quote
+                    let quote = bytes[i];
+                    i += 1;
+                    while i < bytes.len() && bytes[i] != quote {
+                        if bytes[i] == b'\\' {
+                            i += 1;
+                        }
+                        i += 1;
+                    }
+                },
+                b'\\' if i == bytes.len() - 1 => self.backslash = true,
+                _ => ()
+            }
+            i += 1;
+        }
+    }
+}
+
+struct Block {
+    indent: usize,
+    kind: BlockKind,
+    type_checking: bool,
+    // Set on `if` / `elif` chains testing `TYPE_CHECKING` so later branches know what they exclude
+    type_checking_test: Option<bool>,
+}
+
+fn block_context(blocks: &[Block]) -> ImportContext {
+    let kinds = blocks.iter().map(|block| block.kind);
+
+    let scope = if kinds.clone().any(|k| k == BlockKind::Function) {
+        ImportScope::Function
+    } else if kinds.clone().any(|k| k == BlockKind::Class) {
+        ImportScope::Class
+    } else {
+        ImportScope::Module
+    };
+
+    ImportContext {
+        scope: scope,
+        conditional: kinds.clone().any(|k| k == BlockKind::Conditional),
+        type_checking: blocks.iter().any(|block| block.type_checking),
+    }
+}
+
+/// Context of every line in a source file, lines belonging to the same logical line share a context.
+pub struct ScopeTracker {
+    contexts: Vec<ImportContext>,
+}
+
+impl ScopeTracker {
+    pub fn new(source: &str) -> ScopeTracker {
+        let mut blocks: Vec<Block> = vec![];
+        let mut continuation = Continuation::default();
+        let mut current = ImportContext::default();
+        let mut contexts = vec![];
+
+        for line in source.lines() {
+            let code = line.trim_start();
+            // Blank and comment lines do not take part in indentation
+            if !continuation.active() && !code.is_empty() && !code.starts_with('#') {
+                let indent = indent_width(line);
+
+                // Dedenting closes all blocks at the same or deeper indentation
+                let mut sibling = None;
+                while let Some(block) = blocks.last() {
+                    if block.indent >= indent {
+                        sibling = blocks.pop().filter(|block| block.indent == indent);
+                    } else {
+                        break
+                    }
+                }
+
+                current = block_context(&blocks);
+                if let Some(kind) = block_kind(code) {
+                    // `else` / `elif` run when the preceding tests failed
+                    let chain = sibling
+                        .filter(|_| is_branch(code))
+                        .and_then(|sibling| sibling.type_checking_test);
+
+                    let (type_checking, type_checking_test) = match (chain, type_checking_test(code)) {
+                        (Some(test), _) => (!test, Some(test)),
+                        (None, Some(test)) => (test, Some(test)),
+                        (None, None) => (false, None),
+                    };
+
+                    blocks.push(Block {
+                        indent: indent,
+                        kind: kind,
+                        type_checking: type_checking,
+                        type_checking_test: type_checking_test,
+                    });
+                }
+            }
+
+            contexts.push(current);
+            continuation.scan(line);
+        }
+
+        ScopeTracker {
+            contexts: contexts
+        }
+    }
+
+    // Lines are 1-based, same as pest positions
+    pub fn context(&self, line: usize) -> ImportContext {
+        self.contexts.get(line - 1).copied().unwrap_or_default()
+    }
+}
@@ -1,9 +1,23 @@
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
+use serde::{Deserialize, Serialize};
 
+use super::scope::ImportContext;
+
+// Positions are 1-based (line, column) pairs of where the statement / alias starts
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Alias {
     pub name: String,
-    pub asname: Option<String>
+    pub asname: Option<String>,
+    pub line: usize,
+    pub column: usize
+}
+
+impl Alias {
+    // `from x import *`
+    pub fn is_star(&self) -> bool {
+        self.name == "*"
+    }
 }
 
 impl IntoPy<PyObject> for Alias {
@@ -20,17 +34,44 @@ impl IntoPy<PyObject> for Alias {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ImportStmt {
     Import {
-        names: Vec<Alias>
+        names: Vec<Alias>,
+        line: usize,
+        column: usize,
+        context: ImportContext
     },
     ImportFrom {
         module: Option<String>,
         names: Vec<Alias>,
-        level: Option<usize>
+        level: Option<usize>,
+        line: usize,
+        column: usize,
+        context: ImportContext
+    },
+    // Call importing a module named by a string literal (ex: `importlib.import_module("pkg.mod")`)
+    Dynamic {
+        // As written (ex: `importlib.import_module`, `__import__`, `pkgutil.resolve_name`)
+        function: String,
+        // Relative when it starts with dots
+        name: String,
+        // Literal `package` argument of `import_module`, relative names without one are relative to the caller
+        package: Option<String>,
+        line: usize,
+        column: usize,
+        context: ImportContext
     },
 }
 
+/// Imports of a module and its literal `__all__`, found in a single pass over the source.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParsedSource {
+    pub imports: Vec<ImportStmt>,
+    // Names of the module level `__all__`, `None` when the module does not define one
+    pub dunder_all: Option<Vec<String>>,
+}
+
 fn alias_vec_to_list(py: Python<'_>, names: Vec<Alias>) -> PyObject {
     let list = PyList::empty(py);
 
@@ -46,10 +87,10 @@ impl IntoPy<PyObject> for ImportStmt {
         let dict = PyDict::new(py);
 
         match self {
-            ImportStmt::Import { names } => {
+            ImportStmt::Import { names, .. } => {
                 dict.set_item("type", "import").unwrap();
 
-                if names.len() != 0 {
+                if !names.is_empty() {
                     dict.set_item(
                         "names", 
                         alias_vec_to_list(
@@ -58,7 +99,7 @@ impl IntoPy<PyObject> for ImportStmt {
                     )).unwrap()
                 }
             },
-            ImportStmt::ImportFrom { module, names, level } => {
+            ImportStmt::ImportFrom { module, names, level, .. } => {
                 dict.set_item("type", "import_from").unwrap();
 
                 if let Some(module) = module {
@@ -68,7 +109,7 @@ impl IntoPy<PyObject> for ImportStmt {
                     dict.set_item("level", level).unwrap();
                 }
 
-                if names.len() != 0 {
+                if !names.is_empty() {
                     dict.set_item(
                         "names", 
                         alias_vec_to_list(
@@ -76,6 +117,15 @@ impl IntoPy<PyObject> for ImportStmt {
                             names
                     )).unwrap()
                 }
+            },
+            ImportStmt::Dynamic { function, name, package, .. } => {
+                dict.set_item("type", "dynamic").unwrap();
+                dict.set_item("function", function).unwrap();
+                dict.set_item("name", name).unwrap();
+
+                if let Some(package) = package {
+                    dict.set_item("package", package).unwrap();
+                }
             }
         }
         dict.into()
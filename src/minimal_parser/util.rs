@@ -16,12 +16,12 @@ pub fn dump_alias(alias: &Vec<Alias>, tab_level: usize) {
 pub fn dump_imports(stmts: &Vec<ImportStmt>) {
     for stmt in stmts {
         match stmt {
-            ImportStmt::Import { names } => {
+            ImportStmt::Import { names, .. } => {
                 println!("Import: {{");
                 dump_alias(names, 1);
                 println!("}}");
             },
-            ImportStmt::ImportFrom { module, names, level } => {
+            ImportStmt::ImportFrom { module, names, level, .. } => {
                 println!("ImportFrom: {{");
                 if let Some(level) = level {
                     println!("  level: {}", level)
@@ -31,6 +31,15 @@ pub fn dump_imports(stmts: &Vec<ImportStmt>) {
                 }
                 dump_alias(names, 1);
                 println!("}}");
+            },
+            ImportStmt::Dynamic { function, name, package, .. } => {
+                println!("Dynamic: {{");
+                println!("  function: {}", function);
+                println!("  name: {}", name);
+                if let Some(package) = package {
+                    println!("  package: {}", package)
+                }
+                println!("}}");
             }
         }
     }
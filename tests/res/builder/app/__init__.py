@@ -0,0 +1,3 @@
+from . import good
+from . import broken
+from . import binary
@@ -0,0 +1,3 @@
+# Goes beyond the top level package
+from ... import nothing
+from . import only_broken
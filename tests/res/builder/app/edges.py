@@ -0,0 +1,2 @@
+import app.edges_only as h
+from . import only_broken
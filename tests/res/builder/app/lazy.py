@@ -0,0 +1,5 @@
+class Lazy:
+    from . import helper
+
+    def load(self):
+        from . import edges_only
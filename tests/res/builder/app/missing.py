@@ -0,0 +1,3 @@
+import not_installed
+from .not_here import thing
+import app.helper.nope
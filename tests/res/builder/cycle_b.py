@@ -0,0 +1,2 @@
+import cycle_c
+from cycle_a import thing
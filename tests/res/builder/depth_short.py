@@ -0,0 +1 @@
+import depth_leaf
@@ -0,0 +1,2 @@
+import depth_long
+import depth_short
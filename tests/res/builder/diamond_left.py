@@ -0,0 +1 @@
+import diamond_bottom
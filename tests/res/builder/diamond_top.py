@@ -0,0 +1,2 @@
+import diamond_left
+import diamond_right
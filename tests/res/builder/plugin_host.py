@@ -0,0 +1,6 @@
+import importlib
+
+# Plugins are only known by name
+alpha = importlib.import_module("plugins.alpha")
+beta = importlib.import_module(".beta", package="plugins")
+HAS_MISSING = importlib.util.find_spec("plugins.missing") is not None
@@ -0,0 +1,3 @@
+import importlib
+
+gamma = importlib.import_module(".gamma", __package__)
@@ -0,0 +1,10 @@
+__all__ = [
+    "alpha",  # A submodule
+    'VALUE',
+]
+__all__ += ("beta",)
+
+VALUE = 1
+
+def f():
+    __all__ = ["gamma"]
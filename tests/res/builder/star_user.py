@@ -0,0 +1 @@
+from star_pkg import *  # noqa
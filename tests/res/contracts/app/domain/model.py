@@ -0,0 +1 @@
+from app.shared import util
@@ -0,0 +1 @@
+from app.plugins import b
@@ -0,0 +1 @@
+from app.storage import db
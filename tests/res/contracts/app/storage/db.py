@@ -0,0 +1,2 @@
+# Imports a higher layer
+from app.api import routes
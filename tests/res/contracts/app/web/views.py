@@ -0,0 +1 @@
+from app.service import orders
@@ -0,0 +1 @@
+import fakepkg.sub
@@ -0,0 +1,5 @@
+import os
+
+
+import legacy_mod
+from fakepkg import sub
@@ -0,0 +1,25 @@
+import importlib
+from importlib import import_module
+
+plugin = importlib.import_module("pkg.plugins.foo")
+sibling = import_module('.sibling', package="pkg")
+current = import_module(
+    ".current",
+    __package__,
+)
+json = __import__("json")  # importlib.import_module("in_comment")
+print("importlib.import_module('in_string')")
+HAS_NUMPY = importlib.util.find_spec("numpy") is not None
+data = pkgutil.get_data("pkg.data", "file.txt")
+handler = pkgutil.resolve_name("pkg.handlers:Handler")
+
+# Not literal, or not the function
+importlib.import_module(name)
+finder.find_spec("not_me")
+my_import_module("not_me_either")
+USAGE = """
+importlib.import_module("in_docstring")
+"""
+
+def load():
+    return importlib.import_module("lazy")
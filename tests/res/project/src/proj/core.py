@@ -0,0 +1 @@
+from proj.util import helper
@@ -0,0 +1 @@
+from proj import core
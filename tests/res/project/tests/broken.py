@@ -0,0 +1,2 @@
+# Goes beyond the top level package
+from ... import nothing
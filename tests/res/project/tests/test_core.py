@@ -0,0 +1 @@
+from proj.core import helper
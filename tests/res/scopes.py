@@ -0,0 +1,41 @@
+import module_level
+
+try:
+    import in_try
+except ImportError:
+    in_try = None
+
+x = (
+    1,
+2)
+import after_brackets
+
+class Config:
+    import in_class
+
+    def method(self):
+        import in_method
+
+def function(
+    a,
+):
+    """
+Docstring at column zero
+    """
+    value = '''
+not a block
+'''
+    if a:
+        import in_function_if
+    import in_function
+
+async def coroutine():
+    async with lock:
+        import in_coroutine
+
+match command:
+    case "go":
+        import in_match
+	
+if True: pass
+import after_one_liner
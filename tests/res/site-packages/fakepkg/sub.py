@@ -0,0 +1,2 @@
+import legacy_mod
+import unowned
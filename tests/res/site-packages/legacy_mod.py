@@ -0,0 +1 @@
+# Installed from an egg-info without a RECORD
@@ -0,0 +1 @@
+# Not listed by any distribution
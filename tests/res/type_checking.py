@@ -0,0 +1,26 @@
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    from checked import Thing
+    try:
+        import checked_nested
+    except ImportError:
+        pass
+else:
+    import runtime_else
+
+if typing.TYPE_CHECKING:  # comment
+    import qualified
+
+if not TYPE_CHECKING:
+    import runtime_negated
+else:
+    import checked_negated_else
+
+def function():
+    if TYPE_CHECKING:
+        import checked_in_function
+    elif sys.version_info >= (3, 8):
+        import runtime_elif
+
+import runtime_after
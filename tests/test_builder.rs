@@ -0,0 +1,272 @@
+use std::path::Path;
+
+use fast_dep::core::{BuildEntry, BuildOptions, DepGraph, DepNode, GraphBuilder, ImportEdge, ImportKind};
+use fast_dep::error::FastDepError;
+use fast_dep::importlib::{ModuleSpec, Resolver};
+use fast_dep::minimal_parser::ImportScope;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+
+fn builder() -> GraphBuilder {
+    GraphBuilder::with_resolver(Resolver::new(vec![RES.to_string()]), false)
+}
+
+#[test]
+fn test_errors_are_recorded() {
+    let graph = builder().build_source("import app\n", None).unwrap();
+
+    // Failing modules are still part of the graph
+    for name in ["app", "app.good", "app.helper", "app.broken", "app.binary"] {
+        assert!(graph.has_node(name), "Missing node: {}", name);
+    }
+    assert_eq!(graph.errors.len(), 2);
+
+    let (name, err) = &graph.errors.iter().find(|(name, _)| name == "app.broken").unwrap();
+    assert_eq!(name, "app.broken");
+    assert!(matches!(err, FastDepError::Resolution { .. }));
+    // Statements after the failing one are still processed
+    assert!(graph.has_node("app.only_broken"));
+
+    let (_, err) = &graph.errors.iter().find(|(name, _)| name == "app.binary").unwrap();
+    assert!(matches!(err, FastDepError::Io { .. }));
+}
+
+#[test]
+fn test_graph_invariants() {
+    let node = |origin: &str| DepNode::new(ModuleSpec::new("mod", Some(origin.to_string()), None), Some(0));
+
+    let mut graph = DepGraph::new();
+    graph.add(node("a/mod.py")).unwrap();
+    assert!(matches!(graph.add(node("a/mod.py")).err(), Some(FastDepError::Graph { .. })));
+
+    // The same name loaded from somewhere else
+    let mut other = DepGraph::new();
+    other.add(node("b/mod.py")).unwrap();
+    assert!(matches!(graph.merge(other), Err(FastDepError::Graph { .. })));
+    assert!(matches!(graph.clone_from("nope"), Err(FastDepError::Graph { .. })));
+}
+
+#[test]
+fn test_relative_import_from_terminal() {
+    let graph = builder().build_source("from . import app\nimport app.helper\n", None).unwrap();
+
+    let (_, err) = &graph.errors.iter().find(|(name, _)| name == "<terminal>").unwrap();
+    assert!(matches!(err, FastDepError::Resolution { .. }));
+    assert!(graph.has_node("app.helper"));
+}
+
+#[test]
+fn test_unresolved_imports() {
+    let graph = builder().build_source("import app.missing\nimport not_installed\n", None).unwrap();
+
+    let mut unresolved: Vec<(&str, &str, &str)> = graph.unresolved.iter().map(|u| {
+        (u.name.as_str(), u.importer.as_str(), u.text.as_str())
+    }).collect();
+    unresolved.sort();
+    assert_eq!(unresolved, vec![
+        ("app.helper.nope", "app.missing", "app.helper.nope"),
+        ("app.not_here", "app.missing", ".not_here"),
+        ("not_installed", "<terminal>", "not_installed"),
+        ("not_installed", "app.missing", "not_installed"),
+    ]);
+
+    // Missing modules are nodes on the graph
+    let node = graph.nodes.get("not_installed").unwrap().borrow();
+    assert!(node.missing);
+    assert!(!graph.nodes.get("app.helper").unwrap().borrow().missing);
+}
+
+#[test]
+fn test_edge_metadata() {
+    let graph = builder().build_source("import app.edges\n", None).unwrap();
+    let node = graph.nodes.get("app.edges").unwrap().borrow();
+    let origin = Some(format!("{}/app/edges.py", RES));
+
+    assert_eq!(node.dependencies.get("app.edges_only").unwrap(), &vec![ImportEdge {
+        file: origin.clone(),
+        line: 1,
+        column: 8,
+        kind: ImportKind::Import,
+        asname: Some("h".to_string()),
+        level: 0,
+        scope: ImportScope::Module,
+        conditional: false,
+        type_checking: false
+    }]);
+    // Parent packages share the statement which caused them
+    assert_eq!(node.dependencies.get("app").unwrap(), &vec![
+        node.dependencies.get("app.edges_only").unwrap()[0].clone(),
+        ImportEdge {
+            file: origin.clone(),
+            line: 2,
+            column: 1,
+            kind: ImportKind::ImportFrom,
+            asname: None,
+            level: 1,
+            scope: ImportScope::Module,
+            conditional: false,
+            type_checking: false
+        }
+    ]);
+    assert_eq!(node.dependencies.get("app.only_broken").unwrap(), &vec![ImportEdge {
+        file: origin.clone(),
+        line: 2,
+        column: 15,
+        kind: ImportKind::ImportFrom,
+        asname: None,
+        level: 1,
+        scope: ImportScope::Module,
+        conditional: false,
+        type_checking: false
+    }]);
+}
+
+#[test]
+fn test_eager_only() {
+    let source = "import app.lazy\n";
+
+    let graph = builder().build_source(source, None).unwrap();
+    assert!(graph.has_node("app.helper"));
+    assert!(graph.has_node("app.edges_only"));
+
+    let options = BuildOptions { eager_only: true, ..Default::default() };
+    let graph = builder().with_options(options).build_source(source, None).unwrap();
+    // Class bodies run on import, function bodies do not
+    assert!(graph.has_node("app.helper"));
+    assert!(!graph.has_node("app.edges_only"));
+}
+
+#[test]
+fn test_type_checking() {
+    let source = "import app.typed\n";
+
+    let graph = builder().build_source(source, None).unwrap();
+    let node = graph.nodes.get("app.typed").unwrap().borrow();
+    assert!(node.dependencies.get("app.edges_only").unwrap()[0].type_checking);
+    assert!(!node.dependencies.get("app.helper").unwrap()[0].type_checking);
+    drop(node);
+
+    let options = BuildOptions { exclude_type_checking: true, ..Default::default() };
+    let graph = builder().with_options(options).build_source(source, None).unwrap();
+    assert!(graph.has_node("app.helper"));
+    assert!(!graph.has_node("app.edges_only"));
+}
+
+#[test]
+fn test_dynamic_imports() {
+    let source = "import plugin_host\n";
+
+    // Not followed by default
+    let graph = builder().build_source(source, None).unwrap();
+    assert!(graph.has_node("plugin_host"));
+    assert!(!graph.has_node("plugins"));
+
+    let options = BuildOptions { follow_dynamic: true, ..Default::default() };
+    let graph = builder().with_options(options.clone()).build_source(source, None).unwrap();
+    for name in ["plugins", "plugins.alpha", "plugins.beta", "plugins.gamma"] {
+        assert!(graph.has_node(name), "Missing node: {}", name);
+    }
+
+    let node = graph.nodes.get("plugin_host").unwrap().borrow();
+    assert_eq!(node.dependencies.get("plugins.beta").unwrap(), &vec![ImportEdge {
+        file: Some(format!("{}/plugin_host.py", RES)),
+        line: 5,
+        column: 8,
+        kind: ImportKind::Dynamic,
+        asname: None,
+        level: 1,
+        scope: ImportScope::Module,
+        conditional: false,
+        type_checking: false
+    }]);
+    drop(node);
+    // Relative to the importing module's package without a literal `package`
+    assert!(graph.nodes.get("plugins").unwrap().borrow().dependencies.contains_key("plugins.gamma"));
+    // `find_spec` of a module which is not installed
+    assert!(graph.unresolved.iter().any(|u| u.name == "plugins.missing" && u.importer == "plugin_host"));
+
+    let parallel = builder().with_options(BuildOptions { parallel: true, ..options }).build_source(source, None).unwrap();
+    assert_eq!(parallel.to_json(false).unwrap(), graph.to_json(false).unwrap());
+}
+
+#[test]
+fn test_build_module() {
+    let graph = builder().build_module("app.edges").unwrap();
+
+    assert!(!graph.has_node("<terminal>"));
+    let node = graph.nodes.get("app.edges").unwrap().borrow();
+    assert_eq!(node.depth, Some(0));
+    // Relative imports resolve against the module's own package
+    assert!(node.dependencies.contains_key("app.only_broken"));
+    drop(node);
+
+    let err = builder().build_module("does_not_exist").err().unwrap();
+    assert!(matches!(err, FastDepError::Resolution { .. }));
+}
+
+#[test]
+fn test_build_file() {
+    let graph = builder().build_file(Path::new(&format!("{}/app/edges.py", RES))).unwrap();
+    assert_eq!(graph.nodes.get("app.edges").unwrap().borrow().depth, Some(0));
+    assert!(graph.has_node("app.only_broken"));
+
+    let graph = builder().build_file(Path::new(&format!("{}/app/__init__.py", RES))).unwrap();
+    assert_eq!(graph.nodes.get("app").unwrap().borrow().depth, Some(0));
+
+    // Files outside of the search paths are scripts
+    let script = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/scopes.py");
+    let graph = builder().build_file(Path::new(script)).unwrap();
+    assert!(graph.has_node("__main__"));
+    assert!(graph.has_node("module_level"));
+
+    // Entries which can not hold the file are skipped, later ones are still tried
+    let file = format!("{}/app/edges.py", RES);
+    let resolver = Resolver::new(vec![file.clone(), RES.to_string()]);
+    let graph = GraphBuilder::with_resolver(resolver, false).build_file(Path::new(&file)).unwrap();
+    assert!(graph.has_node("app.edges"));
+    assert!(!graph.has_node("__main__"));
+
+    let err = builder().build_file(Path::new("does/not/exist.py")).err().unwrap();
+    assert!(matches!(err, FastDepError::Io { .. }));
+}
+
+#[test]
+fn test_build_many() {
+    let script = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/scopes.py");
+    let entries = [
+        BuildEntry::parse("diamond_top"),
+        BuildEntry::parse("diamond_left"),
+        BuildEntry::parse("cycle_a"),
+        BuildEntry::parse(script),
+    ];
+    let graph = builder().build_many(&entries).unwrap();
+
+    assert_eq!(graph.roots, vec!["diamond_top", "diamond_left", "cycle_a", script]);
+    assert!(!graph.has_node("<terminal>"));
+    assert!(graph.has_node("module_level"));
+
+    // Shortest distance from any root
+    assert_eq!(graph.nodes.get("diamond_left").unwrap().borrow().depth, Some(0));
+    assert_eq!(graph.nodes.get("diamond_bottom").unwrap().borrow().depth, Some(1));
+
+    let depths = graph.depths_from("diamond_top").unwrap();
+    assert_eq!(depths.get("diamond_bottom"), Some(&2));
+    assert!(!depths.contains_key("cycle_a"));
+    assert_eq!(graph.depths_from("cycle_a").unwrap().len(), 3);
+
+    let err = builder().build_many(&[BuildEntry::parse("does_not_exist")]).err().unwrap();
+    assert!(matches!(err, FastDepError::Resolution { .. }));
+}
+
+#[test]
+fn test_star_import() {
+    let graph = builder().build_module("star_user").unwrap();
+
+    // Submodules named in `__all__`, other names are not modules
+    let node = graph.nodes.get("star_user").unwrap().borrow();
+    let mut deps: Vec<&String> = node.dependencies.keys().collect();
+    deps.sort();
+    assert_eq!(deps, vec!["star_pkg", "star_pkg.alpha", "star_pkg.beta"]);
+    assert_eq!(node.dependencies.get("star_pkg.alpha").unwrap()[0].line, 1);
+    assert!(!graph.has_node("star_pkg.gamma"));
+}
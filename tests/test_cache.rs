@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use fast_dep::core::{BuildEntry, BuildOptions, DiskCache, GraphBuilder, CACHE_FILE};
+use fast_dep::importlib::Resolver;
+
+// Fresh directory per test holding a copy of the modules which are modified
+fn workspace(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("fast_dep_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("site")).unwrap();
+    fs::write(dir.join("site/cached_a.py"), "import cached_b\n").unwrap();
+    fs::write(dir.join("site/cached_b.py"), "# Imports nothing\n").unwrap();
+    fs::write(dir.join("site/cached_c.py"), "").unwrap();
+    dir
+}
+
+fn builder(dir: &Path) -> GraphBuilder {
+    let resolver = Resolver::new(vec![dir.join("site").to_string_lossy().to_string()]);
+    GraphBuilder::with_resolver(resolver, false)
+        .with_cache_dir(&dir.join("cache"))
+        .unwrap()
+}
+
+#[test]
+fn test_reused_between_builders() {
+    let dir = workspace("reuse");
+
+    let mut first = builder(&dir);
+    first.build_source("import cached_a\n", None).unwrap();
+    assert_eq!(first.disk_cache().unwrap().misses, 2);
+    assert!(dir.join("cache").join(CACHE_FILE).is_file());
+
+    let mut second = builder(&dir);
+    let graph = second.build_source("import cached_a\n", None).unwrap();
+    assert_eq!(second.disk_cache().unwrap().hits, 2);
+    assert_eq!(second.disk_cache().unwrap().misses, 0);
+    assert!(graph.has_node("cached_b"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_parallel_roots_share_prefetch() {
+    let dir = workspace("parallel");
+    fs::write(dir.join("site/cached_d.py"), "import cached_b\n").unwrap();
+    let entries = vec![BuildEntry::Module("cached_a".to_string()), BuildEntry::Module("cached_d".to_string())];
+
+    builder(&dir).build_many(&entries).unwrap();
+
+    // `cached_b` is imported by both roots, it is only looked up for the first one
+    let options = BuildOptions { parallel: true, ..Default::default() };
+    let mut parallel = builder(&dir).with_options(options);
+    let graph = parallel.build_many(&entries).unwrap();
+    assert_eq!(parallel.disk_cache().unwrap().hits, 3);
+    assert!(graph.has_node("cached_b"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_star_import_from_cache() {
+    let dir = workspace("star");
+    fs::create_dir_all(dir.join("site/cached_pkg")).unwrap();
+    fs::write(dir.join("site/cached_pkg/__init__.py"), "__all__ = ['one']\n").unwrap();
+    fs::write(dir.join("site/cached_pkg/one.py"), "").unwrap();
+    fs::write(dir.join("site/cached_pkg/two.py"), "").unwrap();
+    builder(&dir).build_source("from cached_pkg import *\n", None).unwrap();
+
+    // `__all__` is cached with the imports of the package
+    for options in [BuildOptions::default(), BuildOptions { parallel: true, ..Default::default() }] {
+        let mut second = builder(&dir).with_options(options);
+        let graph = second.build_source("from cached_pkg import *\n", None).unwrap();
+        assert_eq!(second.disk_cache().unwrap().hits, 2);
+        assert_eq!(second.disk_cache().unwrap().misses, 0);
+        assert!(graph.has_node("cached_pkg.one"));
+        assert!(!graph.has_node("cached_pkg.two"));
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_invalidated_on_change() {
+    let dir = workspace("invalidate");
+    builder(&dir).build_source("import cached_a\n", None).unwrap();
+
+    // Same size, different content, within the same mtime tick
+    let path = dir.join("site/cached_a.py");
+    let modified = fs::metadata(&path).unwrap().modified().unwrap();
+    fs::write(&path, "import cached_c\n").unwrap();
+    fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), modified);
+    let mut second = builder(&dir);
+    let graph = second.build_source("import cached_a\n", None).unwrap();
+    assert!(graph.has_node("cached_c"));
+    assert!(!graph.has_node("cached_b"));
+    assert_eq!(second.disk_cache().unwrap().misses, 2);
+
+    // Rewriting the same content keeps the parsed imports
+    fs::write(dir.join("site/cached_a.py"), "import cached_c\n").unwrap();
+    let mut third = builder(&dir);
+    third.build_source("import cached_a\n", None).unwrap();
+    assert_eq!(third.disk_cache().unwrap().misses, 0);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_unreadable_cache_is_discarded() {
+    let dir = workspace("corrupt");
+    fs::create_dir_all(dir.join("cache")).unwrap();
+    fs::write(dir.join("cache").join(CACHE_FILE), "not json").unwrap();
+
+    let mut builder = builder(&dir);
+    assert!(builder.build_source("import cached_a\n", None).unwrap().has_node("cached_b"));
+    assert_eq!(builder.disk_cache().unwrap().misses, 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_specs_kept_between_processes() {
+    let dir = workspace("aliases");
+    let site = dir.join("site").to_string_lossy().to_string();
+
+    let mut resolver = Resolver::new(vec![site.clone()]);
+    resolver.aliases.insert("alias_b".to_string(), "cached_b".to_string());
+    GraphBuilder::with_resolver(resolver, false)
+        .with_cache_dir(&dir.join("cache"))
+        .unwrap()
+        .build_source("import cached_a\nimport alias_b\n", None)
+        .unwrap();
+
+    // Aliases differ between processes, they do not invalidate the cached specs and are not cached themselves
+    let mut resolver = Resolver::new(vec![site]);
+    let mut disk_cache = DiskCache::load(&dir.join("cache"), &resolver).unwrap();
+    disk_cache.seed(&mut resolver);
+    let mut names: Vec<&String> = resolver.resolved().map(|(name, _)| name).collect();
+    names.sort();
+    assert_eq!(names, vec!["cached_a", "cached_b"]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use fast_dep::core::{Classifier, DepGraph, GraphBuilder, ModuleCategory};
+use fast_dep::importlib::{ModuleSpec, Resolver};
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+
+fn classifier() -> Classifier {
+    let mut classifier = Classifier::for_resolver(&Resolver::new(vec![]));
+    classifier.stdlib_modules = ["os", "json", "_json"].iter().map(|name| name.to_string()).collect();
+    classifier.stdlib_paths = vec![PathBuf::from("/usr/lib/python3.12")];
+    classifier.site_paths = vec![PathBuf::from("/usr/lib/python3.12/site-packages")];
+    classifier.project_roots = vec![PathBuf::from("/work/project")];
+    classifier
+}
+
+fn spec(name: &str, origin: Option<&str>) -> ModuleSpec {
+    ModuleSpec::new(name, origin.map(|origin| origin.to_string()), None)
+}
+
+#[test]
+fn test_classify_specs() {
+    let classifier = classifier();
+    let classify = |name: &str, origin: Option<&str>| classifier.classify(&spec(name, origin), false);
+
+    assert_eq!(classify("sys", Some("built-in")), ModuleCategory::BuiltIn);
+    assert_eq!(classify("zipimport", Some("frozen")), ModuleCategory::Stdlib);
+    assert_eq!(classify("os", Some("/usr/lib/python3.12/os.py")), ModuleCategory::Stdlib);
+    assert_eq!(classify("_json", Some("/usr/lib/python3.12/lib-dynload/_json.cpython-312-x86_64-linux-gnu.so")), ModuleCategory::Stdlib);
+    assert_eq!(classify("requests", Some("/usr/lib/python3.12/site-packages/requests/__init__.py")), ModuleCategory::ThirdParty);
+    assert_eq!(classify("numpy.core._multiarray_umath", Some("/usr/lib/python3.12/site-packages/numpy/core/_multiarray_umath.so")), ModuleCategory::Extension);
+    // A virtual environment inside the project is not part of it
+    assert_eq!(classify("attr", Some("/work/project/.venv/lib/python3.12/site-packages/attr/__init__.py")), ModuleCategory::ThirdParty);
+    assert_eq!(classify("app.models", Some("/work/project/src/app/models.py")), ModuleCategory::FirstParty);
+    assert_eq!(classify("vendored", Some("/opt/libs/vendored.py")), ModuleCategory::ThirdParty);
+    // Only known by name
+    assert_eq!(classify("json", None), ModuleCategory::Stdlib);
+    assert_eq!(classify("<terminal>", None), ModuleCategory::FirstParty);
+    assert_eq!(classifier.classify(&spec("nothing", None), true), ModuleCategory::Unknown);
+
+    let namespace = ModuleSpec::new("app.plugins", None, Some(vec!["/work/project/src/app/plugins".to_string()]));
+    assert_eq!(classifier.classify(&namespace, false), ModuleCategory::FirstParty);
+
+    // Without project roots every file outside of the interpreter's locations is first-party
+    let mut classifier = classifier.clone();
+    classifier.project_roots.clear();
+    assert_eq!(classifier.classify(&spec("vendored", Some("/opt/libs/vendored.py")), false), ModuleCategory::FirstParty);
+}
+
+fn build() -> DepGraph {
+    let mut classifier = Classifier::for_resolver(&Resolver::new(vec![]));
+    classifier.stdlib_modules = ["cycle_a"].iter().map(|name| name.to_string()).collect();
+    classifier.add_project_root(&PathBuf::from(RES).join("app"));
+
+    let mut builder = GraphBuilder::with_resolver(Resolver::new(vec![RES.to_string()]), false).with_classifier(classifier);
+    builder.build_source("import app.good\nimport diamond_left\nimport cycle_a\nimport nothing\n", None).unwrap()
+}
+
+#[test]
+fn test_by_category() {
+    let graph = build();
+
+    assert_eq!(graph.by_category(ModuleCategory::FirstParty), vec![
+        "<terminal>",
+        "app",
+        "app.binary",
+        "app.broken",
+        "app.good",
+        "app.helper",
+        "app.only_broken",
+    ]);
+    assert_eq!(graph.by_category(ModuleCategory::Stdlib), vec!["cycle_a"]);
+    assert_eq!(graph.by_category(ModuleCategory::ThirdParty), vec!["cycle_b", "cycle_c", "diamond_bottom", "diamond_left"]);
+    assert_eq!(graph.by_category(ModuleCategory::Unknown), vec!["nothing"]);
+}
+
+#[test]
+fn test_filter_categories() {
+    let graph = build();
+    let filtered = graph.filter_categories(&[ModuleCategory::FirstParty, ModuleCategory::ThirdParty]).unwrap();
+
+    assert!(!filtered.has_node("cycle_a"));
+    assert!(filtered.has_node("cycle_b"));
+    // Imports of removed modules go with them
+    assert!(!filtered.nodes.get("cycle_b").unwrap().borrow().dependents.contains("cycle_a"));
+    assert!(!filtered.nodes.get("<terminal>").unwrap().borrow().dependencies.contains_key("cycle_a"));
+    assert_eq!(filtered.unresolved.len(), 1);
+}
+
+#[test]
+fn test_categories_are_serialized() {
+    let graph = build();
+    let loaded = DepGraph::from_json(&graph.to_json(false).unwrap()).unwrap();
+    assert_eq!(loaded.by_category(ModuleCategory::Stdlib), vec!["cycle_a"]);
+
+    // Documents written before categories load as unknown
+    let data = graph.to_json(false).unwrap().replace(",\"category\":\"stdlib\"", "");
+    let loaded = DepGraph::from_json(&data).unwrap();
+    assert_eq!(loaded.by_category(ModuleCategory::Unknown), vec!["cycle_a", "nothing"]);
+}
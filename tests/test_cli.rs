@@ -0,0 +1,123 @@
+use std::process::{Command, Output};
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+const PROJECT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/project");
+const CONTRACTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/contracts");
+
+fn fast_dep(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fast-dep"))
+        .args(args)
+        .args(["--no-python", "--path", RES])
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_tree() {
+    let output = fast_dep(&["tree", "diamond_top"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "\
+diamond_top
+├── diamond_left
+│   └── diamond_bottom
+└── diamond_right
+    ├── diamond_bottom
+    └── diamond_left (*)
+");
+
+    let output = fast_dep(&["tree", "--depth", "1", "diamond_top"]);
+    assert_eq!(stdout(&output), "diamond_top\n├── diamond_left\n└── diamond_right\n");
+}
+
+#[test]
+fn test_build() {
+    let output = fast_dep(&["build", "diamond_top"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("4 modules, 5 dependencies from diamond_top\n"));
+
+    let output = fast_dep(&["build", "--project", PROJECT]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("tests.broken: "));
+}
+
+#[test]
+fn test_path() {
+    let output = fast_dep(&["path", "--from", "diamond_top", "--to", "diamond_bottom", "diamond_top"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "diamond_top -> diamond_left -> diamond_bottom\n");
+
+    let output = fast_dep(&["path", "--from", "diamond_bottom", "--to", "diamond_top", "diamond_top"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_cycles() {
+    let output = fast_dep(&["cycles", "--shortest", "cycle_a"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with("cycle_a -> cycle_b -> cycle_a\n"));
+
+    let output = fast_dep(&["cycles", "diamond_top"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_export_and_load() {
+    let path = std::env::temp_dir().join(format!("fast_dep_cli_{}.json", std::process::id()));
+    let path_str = path.to_string_lossy().to_string();
+
+    let output = fast_dep(&["export", "--format", "json", "-o", &path_str, "diamond_top"]);
+    assert!(output.status.success());
+    let output = fast_dep(&["tree", "--graph", &path_str]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("diamond_top\n├── diamond_left\n"));
+
+    let output = fast_dep(&["export", "--format", "mermaid", "--graph", &path_str]);
+    assert!(stdout(&output).contains("n3 --> n1"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_diff() {
+    let dir = std::env::temp_dir();
+    let base = dir.join(format!("fast_dep_cli_base_{}.json", std::process::id())).to_string_lossy().to_string();
+    let head = dir.join(format!("fast_dep_cli_head_{}.json", std::process::id())).to_string_lossy().to_string();
+    fast_dep(&["export", "-o", &base, "diamond_top"]);
+    fast_dep(&["export", "-o", &head, "diamond_right"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fast-dep")).args(["diff", &base, &head]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).starts_with("Removed modules (1):\n  - diamond_top\n"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fast-dep")).args(["diff", "--json", &base, &base]).output().unwrap();
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("{\"added_nodes\":[]"));
+
+    std::fs::remove_file(&base).unwrap();
+    std::fs::remove_file(&head).unwrap();
+}
+
+#[test]
+fn test_contracts() {
+    let contracts = format!("{}/contracts.toml", CONTRACTS);
+    let output = fast_dep(&["contracts", "--contracts", &contracts, "--project", CONTRACTS, "--path", CONTRACTS]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("Layers: app.storage.db -> app.api.routes\n    "));
+    assert!(stdout(&output).contains("app/storage/db.py:2 imports app.api.routes\n"));
+}
+
+#[test]
+fn test_errors() {
+    let output = fast_dep(&["tree", "does_not_exist"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("error: "));
+
+    // No root to build from
+    let output = fast_dep(&["tree"]);
+    assert_eq!(output.status.code(), Some(2));
+}
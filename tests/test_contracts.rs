@@ -0,0 +1,104 @@
+use std::path::Path;
+
+use fast_dep::core::{Contract, DepGraph, GraphBuilder, load_contracts};
+use fast_dep::error::FastDepError;
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/contracts");
+
+fn build() -> DepGraph {
+    let resolver = Resolver::new(vec![RES.to_string()]);
+    GraphBuilder::with_resolver(resolver, false).build_project(Path::new(RES)).unwrap()
+}
+
+fn contracts() -> Vec<Contract> {
+    load_contracts(&Path::new(RES).join("contracts.toml")).unwrap()
+}
+
+fn strings(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_load_contracts() {
+    let contracts = contracts();
+
+    assert_eq!(contracts.len(), 4);
+    assert_eq!(contracts[2], Contract::Layers {
+        name: "Layers".to_string(),
+        layers: strings(&["api", "service", "storage"]),
+        containers: strings(&["app"]),
+    });
+    assert!(matches!(contracts[1], Contract::Forbidden { allow_indirect: true, .. }));
+}
+
+#[test]
+fn test_invalid_contracts() {
+    let dir = std::env::temp_dir().join(format!("fast_dep_contracts_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("contracts.toml");
+
+    for data in [
+        "[[contracts]]\nname = \"a\"\ntype = \"unknown\"\n",
+        // Typo in a field
+        "[[contracts]]\nname = \"a\"\ntype = \"independence\"\nmodule = [\"a\"]\n",
+        "[[contracts]\n",
+    ] {
+        std::fs::write(&path, data).unwrap();
+        assert!(matches!(load_contracts(&path).unwrap_err(), FastDepError::Parse { .. }));
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_forbidden() {
+    let graph = build();
+    let violations = graph.check_contracts(&contracts()[..2]);
+
+    // Through `app.shared.util`, the direct imports only contract is kept
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].contract, "Domain is independent of the web");
+    assert_eq!(violations[0].path, strings(&["app.domain.model", "app.shared.util", "app.web.views"]));
+    assert!(violations[0].edges[1].file.as_ref().unwrap().ends_with("app/shared/util.py"));
+}
+
+#[test]
+fn test_layers() {
+    let graph = build();
+    let violations = graph.check_contracts(&contracts()[2..3]);
+
+    // `from app.api import routes` is reported once
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, strings(&["app.storage.db", "app.api.routes"]));
+    assert_eq!(violations[0].edges[0].line, 2);
+}
+
+#[test]
+fn test_layers_through_other_layer() {
+    let graph = build();
+    let layers = |layers: &[&str]| vec![Contract::Layers {
+        name: "Layers".to_string(),
+        layers: strings(layers),
+        containers: vec![],
+    }];
+
+    // `app.api` is not a layer, the chain goes through it
+    let violations = graph.check_contracts(&layers(&["app.service", "app.storage"]));
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, strings(&["app.storage.db", "app.api.routes", "app.service.orders"]));
+
+    // Only reported as `app.storage` importing `app.api`
+    let violations = graph.check_contracts(&layers(&["app.api", "app.service", "app.storage"]));
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, strings(&["app.storage.db", "app.api.routes"]));
+}
+
+#[test]
+fn test_independence() {
+    let graph = build();
+    let violations = graph.check_contracts(&contracts()[3..]);
+
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].path, strings(&["app.plugins.a", "app.plugins.b"]));
+}
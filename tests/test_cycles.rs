@@ -0,0 +1,47 @@
+use fast_dep::core::GraphBuilder;
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+
+fn builder() -> GraphBuilder {
+    GraphBuilder::with_resolver(Resolver::new(vec![RES.to_string()]), false)
+}
+
+#[test]
+fn test_strongly_connected_components() {
+    let graph = builder().build_source("import cycle_a\nimport acyclic\n", None).unwrap();
+
+    let components = graph.strongly_connected_components();
+    assert!(components.contains(&vec![
+        "cycle_a".to_string(),
+        "cycle_b".to_string(),
+        "cycle_c".to_string()
+    ]));
+    // Every node belongs to exactly one component
+    assert_eq!(components.iter().map(|c| c.len()).sum::<usize>(), graph.size());
+}
+
+#[test]
+fn test_cycles() {
+    let graph = builder().build_source("import cycle_a\nimport acyclic\n", None).unwrap();
+
+    let cycles = graph.cycles(false);
+    assert_eq!(cycles.len(), 1);
+    assert_eq!(cycles[0].modules, vec!["cycle_a", "cycle_b", "cycle_c"]);
+    assert_eq!(cycles[0].path, None);
+
+    let cycles = graph.cycles(true);
+    assert_eq!(cycles[0].path, Some(vec![
+        "cycle_a".to_string(),
+        "cycle_b".to_string(),
+        "cycle_a".to_string()
+    ]));
+    let lines: Vec<usize> = cycles[0].edges.as_ref().unwrap().iter().map(|edge| edge.line).collect();
+    assert_eq!(lines, vec![1, 2]);
+}
+
+#[test]
+fn test_no_cycles() {
+    let graph = builder().build_source("import acyclic\n", None).unwrap();
+    assert!(graph.cycles(true).is_empty());
+}
@@ -0,0 +1,67 @@
+use fast_dep::core::{DepGraph, DepthChange, GraphBuilder};
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+
+fn build(name: &str) -> DepGraph {
+    let resolver = Resolver::new(vec![RES.to_string()]);
+    GraphBuilder::with_resolver(resolver, false).build_module(name).unwrap()
+}
+
+fn edge(from: &str, to: &str) -> (String, String) {
+    (from.to_string(), to.to_string())
+}
+
+#[test]
+fn test_diff() {
+    let base = build("diamond_top");
+    let head = build("diamond_right");
+    let diff = base.diff(&head);
+
+    assert!(diff.added_nodes.is_empty());
+    assert_eq!(diff.removed_nodes, vec!["diamond_top"]);
+    assert!(diff.added_edges.is_empty());
+    assert_eq!(diff.removed_edges, vec![edge("diamond_top", "diamond_left"), edge("diamond_top", "diamond_right")]);
+    assert_eq!(diff.depth_changes, vec![
+        DepthChange { name: "diamond_bottom".to_string(), old: Some(2), new: Some(1) },
+        DepthChange { name: "diamond_right".to_string(), old: Some(1), new: Some(0) },
+    ]);
+
+    let reverse = head.diff(&base);
+    assert_eq!(reverse.added_nodes, diff.removed_nodes);
+    assert_eq!(reverse.added_edges, diff.removed_edges);
+
+    assert!(base.diff(&base).is_empty());
+}
+
+#[test]
+fn test_diff_loaded() {
+    let base = build("diamond_top");
+    let head = build("cycle_a");
+
+    let loaded_base = DepGraph::from_json(&base.to_json(false).unwrap()).unwrap();
+    let loaded_head = DepGraph::from_json(&head.to_json(false).unwrap()).unwrap();
+    assert_eq!(loaded_base.diff(&loaded_head), base.diff(&head));
+}
+
+#[test]
+fn test_renderings() {
+    let diff = build("diamond_top").diff(&build("diamond_right"));
+
+    assert_eq!(diff.to_text(), "\
+Removed modules (1):
+  - diamond_top
+Removed imports (2):
+  - diamond_top -> diamond_left
+  - diamond_top -> diamond_right
+Depth changes (2):
+  ~ diamond_bottom: 2 -> 1
+  ~ diamond_right: 1 -> 0
+");
+    assert_eq!(build("diamond_top").diff(&build("diamond_top")).to_text(), "No changes\n");
+
+    let json: serde_json::Value = serde_json::from_str(&diff.to_json(false).unwrap()).unwrap();
+    assert_eq!(json["removed_nodes"], serde_json::json!(["diamond_top"]));
+    assert_eq!(json["removed_edges"][0], serde_json::json!({"from": "diamond_top", "to": "diamond_left"}));
+    assert_eq!(json["depth_changes"][1], serde_json::json!({"name": "diamond_right", "old": 1, "new": 0}));
+}
@@ -0,0 +1,64 @@
+use fast_dep::core::{DepGraph, GraphBuilder, ModuleCategory};
+use fast_dep::importlib::{Distribution, DistributionIndex, ModuleSpec, Resolver};
+
+const SITE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/site-packages");
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+
+fn distribution(name: &str, version: &str) -> Distribution {
+    Distribution {
+        name: name.to_string(),
+        version: version.to_string(),
+    }
+}
+
+fn build() -> DepGraph {
+    let resolver = Resolver::new(vec![RES.to_string(), SITE.to_string()]);
+    let mut builder = GraphBuilder::with_resolver(resolver, false);
+    builder.build_source("import fakepkg\nimport diamond_left\n", None).unwrap()
+}
+
+#[test]
+fn test_index() {
+    let index = DistributionIndex::scan(&[SITE.to_string()]);
+
+    // From `RECORD`
+    let spec = ModuleSpec::new("fakepkg.sub", Some(format!("{}/fakepkg/sub.py", SITE)), None);
+    assert_eq!(index.for_spec(&spec, false), Some(&distribution("FakePkg", "1.2.0")));
+
+    // Quoted CSV fields
+    for file in ["data,v2.py", "say \"hi\".py"] {
+        let spec = ModuleSpec::new("fakepkg.data", Some(format!("{}/fakepkg/{}", SITE, file)), None);
+        assert_eq!(index.for_spec(&spec, false), Some(&distribution("FakePkg", "1.2.0")));
+    }
+
+    // From `top_level.txt` only
+    let spec = ModuleSpec::new("legacy_mod", Some(format!("{}/legacy_mod.py", SITE)), None);
+    assert_eq!(index.for_spec(&spec, false), None);
+    assert_eq!(index.for_spec(&spec, true), Some(&distribution("legacy", "0.1")));
+
+    let spec = ModuleSpec::new("unowned", Some(format!("{}/unowned.py", SITE)), None);
+    assert_eq!(index.for_spec(&spec, true), None);
+}
+
+#[test]
+fn test_attach_distributions() {
+    let graph = build();
+
+    let node = |name: &str| graph.nodes.get(name).unwrap().borrow().clone();
+    assert_eq!(node("fakepkg").category, ModuleCategory::ThirdParty);
+    assert_eq!(node("fakepkg").distribution, Some(distribution("FakePkg", "1.2.0")));
+    assert_eq!(node("fakepkg.sub").distribution, Some(distribution("FakePkg", "1.2.0")));
+    assert_eq!(node("legacy_mod").distribution, Some(distribution("legacy", "0.1")));
+    assert_eq!(node("unowned").distribution, None);
+    // First-party modules are never looked up
+    assert_eq!(node("diamond_left").distribution, None);
+}
+
+#[test]
+fn test_distributions() {
+    let graph = build();
+    assert_eq!(graph.distributions(), vec![distribution("FakePkg", "1.2.0"), distribution("legacy", "0.1")]);
+
+    let loaded = DepGraph::from_json(&graph.to_json(false).unwrap()).unwrap();
+    assert_eq!(loaded.distributions(), graph.distributions());
+}
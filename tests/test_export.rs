@@ -0,0 +1,65 @@
+use fast_dep::core::{ExportOptions, GraphBuilder};
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+
+fn builder() -> GraphBuilder {
+    GraphBuilder::with_resolver(Resolver::new(vec![RES.to_string()]), false)
+}
+
+#[test]
+fn test_dot() {
+    let graph = builder().build_source("import diamond_top\n", None).unwrap();
+
+    let options = ExportOptions { max_depth: Some(2), ..Default::default() };
+    assert_eq!(graph.to_dot(&options), concat!(
+        "digraph dependencies {\n",
+        "    node [shape=box];\n",
+        "    \"<terminal>\";\n",
+        "    \"diamond_left\";\n",
+        "    \"diamond_right\";\n",
+        "    \"diamond_top\";\n",
+        "    \"<terminal>\" -> \"diamond_top\";\n",
+        "    \"diamond_right\" -> \"diamond_left\";\n",
+        "    \"diamond_top\" -> \"diamond_left\";\n",
+        "    \"diamond_top\" -> \"diamond_right\";\n",
+        "}\n",
+    ));
+}
+
+#[test]
+fn test_dot_clusters() {
+    let graph = builder().build_source("import app.helper\nimport not_installed\n", None).unwrap();
+
+    let dot = graph.to_dot(&ExportOptions { color: true, ..Default::default() });
+    assert!(dot.contains("    subgraph \"cluster_app\" {\n        label=\"app\";\n"));
+    assert!(dot.contains("        \"app.helper\" [style=filled, fillcolor=\"#d5e8d4\"];\n"));
+    assert!(dot.contains("    \"not_installed\" [style=filled, fillcolor=\"#f8cecc\"];\n"));
+
+    let collapsed = graph.to_dot(&ExportOptions { collapse_packages: true, ..Default::default() });
+    assert!(!collapsed.contains("cluster_"));
+    assert!(!collapsed.contains("app.helper"));
+    assert!(collapsed.contains("    \"<terminal>\" -> \"app\";\n"));
+    assert!(!collapsed.contains("    \"app\" -> \"app\";\n"));
+}
+
+#[test]
+fn test_mermaid() {
+    let graph = builder().build_source("import app.good\n", None).unwrap();
+
+    let options = ExportOptions { max_depth: Some(1), color: true, ..Default::default() };
+    assert_eq!(graph.to_mermaid(&options), concat!(
+        "flowchart LR\n",
+        "    n0[\"#lt;terminal#gt;\"]\n",
+        "    subgraph pkg_app[\"app\"]\n",
+        "        n1[\"app\"]\n",
+        "        n2[\"app.good\"]\n",
+        "    end\n",
+        "    n0 --> n1\n",
+        "    n0 --> n2\n",
+        "    n1 --> n2\n",
+        "    n2 --> n1\n",
+        "    classDef first_party fill:#d5e8d4\n",
+        "    class n0,n1,n2 first_party\n",
+    ));
+}
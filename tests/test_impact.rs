@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use fast_dep::core::{DepGraph, GraphBuilder, DEFAULT_TEST_PATTERNS};
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/impact");
+
+fn build() -> DepGraph {
+    let resolver = Resolver::new(vec![RES.to_string()]);
+    GraphBuilder::with_resolver(resolver, false).build_project(Path::new(RES)).unwrap()
+}
+
+fn files(files: &[&str]) -> Vec<String> {
+    files.iter().map(|file| format!("{}/{}", RES, file)).collect()
+}
+
+fn patterns() -> Vec<String> {
+    DEFAULT_TEST_PATTERNS.iter().map(|pattern| pattern.to_string()).collect()
+}
+
+#[test]
+fn test_modules_for_files() {
+    let graph = build();
+
+    assert_eq!(graph.modules_for_files(&files(&["pkg/b.py", "pkg/__init__.py"])), vec!["pkg", "pkg.b"]);
+    // Not normalized paths and files which are not modules
+    assert_eq!(graph.modules_for_files(&files(&["tests/../pkg/c.py", "README.md"])), vec!["pkg.c"]);
+}
+
+#[test]
+fn test_affected_tests() {
+    let graph = build();
+
+    // Through `pkg.a` and the `tests.helpers` module, which is not a test
+    assert_eq!(graph.affected_tests(&files(&["pkg/b.py"]), &patterns()), vec!["tests.b_test", "tests.test_a"]);
+    assert_eq!(graph.affected_tests(&files(&["pkg/c.py"]), &patterns()), vec!["tests.test_c"]);
+    // Importing a submodule runs its package
+    assert_eq!(
+        graph.affected_tests(&files(&["pkg/__init__.py"]), &patterns()),
+        vec!["tests.b_test", "tests.test_a", "tests.test_c"]
+    );
+    assert_eq!(graph.affected_tests(&files(&["tests/test_c.py"]), &patterns()), vec!["tests.test_c"]);
+    assert!(graph.affected_tests(&files(&["README.md"]), &patterns()).is_empty());
+
+    let patterns = vec!["test_*.py".to_string()];
+    assert_eq!(graph.affected_tests(&files(&["pkg/b.py"]), &patterns), vec!["tests.test_a"]);
+}
+
+#[test]
+fn test_affected_modules() {
+    let graph = build();
+
+    let mut affected: Vec<String> = graph.affected_modules(&["pkg.a".to_string()]).into_iter().collect();
+    affected.sort();
+    assert_eq!(affected, vec!["pkg.a", "tests.test_a"]);
+}
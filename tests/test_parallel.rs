@@ -0,0 +1,30 @@
+use fast_dep::core::{BuildOptions, GraphBuilder};
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+const SOURCE: &str = "import app\nimport app.edges\nimport app.lazy\nimport app.missing\nimport diamond_top\nimport cycle_a\nimport star_user\n";
+
+fn builder(options: BuildOptions) -> GraphBuilder {
+    GraphBuilder::with_resolver(Resolver::new(vec![RES.to_string()]), false).with_options(options)
+}
+
+#[test]
+fn test_same_graph_as_serial() {
+    let serial = builder(BuildOptions::default()).build_source(SOURCE, None).unwrap();
+
+    for threads in [0, 1, 4] {
+        let options = BuildOptions { parallel: true, threads: threads, ..Default::default() };
+        let parallel = builder(options).build_source(SOURCE, None).unwrap();
+        assert_eq!(parallel.to_json(true).unwrap(), serial.to_json(true).unwrap());
+    }
+}
+
+#[test]
+fn test_options_apply_to_parallel_builds() {
+    let options = BuildOptions { eager_only: true, ..Default::default() };
+    let serial = builder(options).build_source(SOURCE, None).unwrap();
+
+    let options = BuildOptions { eager_only: true, parallel: true, ..Default::default() };
+    let parallel = builder(options).build_source(SOURCE, None).unwrap();
+    assert_eq!(parallel.to_json(false).unwrap(), serial.to_json(false).unwrap());
+}
@@ -4,5 +4,145 @@ use fast_dep::minimal_parser;
 fn test_parser() {
     let file_contents = include_str!("res/test.py");
 
-    minimal_parser::parse(&file_contents);
-}
\ No newline at end of file
+    minimal_parser::parse(file_contents).unwrap();
+}
+#[test]
+fn test_positions() {
+    let source = "import os\n\ndef f():\n    from .pkg import (\n        a as b,\n    )\n";
+    let stmts = minimal_parser::parse(source).unwrap();
+    assert_eq!(stmts.len(), 2);
+
+    match &stmts[0] {
+        minimal_parser::ImportStmt::Import { names, line, column, .. } => {
+            assert_eq!((*line, *column), (1, 1));
+            assert_eq!((names[0].line, names[0].column), (1, 8));
+        },
+        _ => panic!("Expected `import` statement")
+    }
+    match &stmts[1] {
+        minimal_parser::ImportStmt::ImportFrom { names, line, column, .. } => {
+            assert_eq!((*line, *column), (4, 5));
+            assert_eq!((names[0].line, names[0].column), (5, 9));
+            assert_eq!(names[0].asname, Some("b".to_string()));
+        },
+        _ => panic!("Expected `from` statement")
+    }
+}
+
+#[test]
+fn test_scopes() {
+    use minimal_parser::{ImportContext, ImportScope, ImportStmt};
+
+    let stmts = minimal_parser::parse(include_str!("res/scopes.py")).unwrap();
+    let contexts: Vec<(String, ImportContext)> = stmts.into_iter().map(|stmt| match stmt {
+        ImportStmt::Import { names, context, .. } => (names[0].name.clone(), context),
+        ImportStmt::ImportFrom { module, context, .. } => (module.unwrap(), context),
+        ImportStmt::Dynamic { name, context, .. } => (name, context),
+    }).collect();
+
+    let context = |scope, conditional| ImportContext { scope: scope, conditional: conditional, type_checking: false };
+    assert_eq!(contexts, vec![
+        ("module_level".to_string(), context(ImportScope::Module, false)),
+        ("in_try".to_string(), context(ImportScope::Module, true)),
+        ("after_brackets".to_string(), context(ImportScope::Module, false)),
+        ("in_class".to_string(), context(ImportScope::Class, false)),
+        ("in_method".to_string(), context(ImportScope::Function, false)),
+        ("in_function_if".to_string(), context(ImportScope::Function, true)),
+        ("in_function".to_string(), context(ImportScope::Function, false)),
+        ("in_coroutine".to_string(), context(ImportScope::Function, true)),
+        ("in_match".to_string(), context(ImportScope::Module, true)),
+        ("after_one_liner".to_string(), context(ImportScope::Module, false)),
+    ]);
+}
+
+#[test]
+fn test_type_checking() {
+    use minimal_parser::ImportStmt;
+
+    let stmts = minimal_parser::parse(include_str!("res/type_checking.py")).unwrap();
+    let flags: Vec<(String, bool)> = stmts.into_iter().map(|stmt| match stmt {
+        ImportStmt::Import { names, context, .. } => (names[0].name.clone(), context.type_checking),
+        ImportStmt::ImportFrom { module, context, .. } => (module.unwrap(), context.type_checking),
+        ImportStmt::Dynamic { name, context, .. } => (name, context.type_checking),
+    }).collect();
+
+    assert_eq!(flags, vec![
+        ("typing".to_string(), false),
+        ("checked".to_string(), true),
+        ("checked_nested".to_string(), true),
+        ("runtime_else".to_string(), false),
+        ("qualified".to_string(), true),
+        ("runtime_negated".to_string(), false),
+        ("checked_negated_else".to_string(), true),
+        ("checked_in_function".to_string(), true),
+        ("runtime_elif".to_string(), false),
+        ("runtime_after".to_string(), false),
+    ]);
+}
+
+#[test]
+fn test_star_import() {
+    use minimal_parser::ImportStmt;
+
+    let stmts = minimal_parser::parse("from os import *\nfrom . import (a)\nfrom .pkg import *  # noqa\n").unwrap();
+    assert_eq!(stmts.len(), 3);
+    match &stmts[0] {
+        ImportStmt::ImportFrom { module, names, .. } => {
+            assert_eq!(module.as_deref(), Some("os"));
+            assert_eq!(names.len(), 1);
+            assert!(names[0].is_star());
+            assert_eq!((names[0].line, names[0].column), (1, 16));
+        },
+        _ => panic!("Expected `from` statement")
+    }
+    match &stmts[2] {
+        ImportStmt::ImportFrom { module, names, level, .. } => {
+            assert_eq!((module.as_deref(), *level), (Some("pkg"), Some(1)));
+            assert!(names[0].is_star());
+        },
+        _ => panic!("Expected `from` statement")
+    }
+}
+
+#[test]
+fn test_dunder_all() {
+    let parsed = minimal_parser::parse_source(include_str!("res/builder/star_pkg/__init__.py")).unwrap();
+    // Extended by `+=`, the assignment in a function is ignored
+    assert_eq!(parsed.dunder_all, Some(vec!["alpha".to_string(), "VALUE".to_string(), "beta".to_string()]));
+    assert_eq!(parsed.imports.len(), 0);
+
+    let parsed = minimal_parser::parse_source("__all__: list = ['a']\n__all__ = []\n").unwrap();
+    assert_eq!(parsed.dunder_all, Some(vec![]));
+
+    // Not literal
+    assert_eq!(minimal_parser::parse_source("__all__ = ['a'] + other.__all__\n").unwrap().dunder_all, None);
+    let parsed = minimal_parser::parse_source("import os\n").unwrap();
+    assert_eq!(parsed.dunder_all, None);
+    assert_eq!(parsed.imports.len(), 1);
+}
+
+#[test]
+fn test_dynamic_imports() {
+    use minimal_parser::{ImportScope, ImportStmt};
+
+    let stmts = minimal_parser::parse(include_str!("res/dynamic.py")).unwrap();
+    let calls: Vec<(String, String, Option<String>, ImportScope)> = stmts.into_iter().filter_map(|stmt| match stmt {
+        ImportStmt::Dynamic { function, name, package, context, .. } => Some((function, name, package, context.scope)),
+        _ => None
+    }).collect();
+
+    let call = |function: &str, name: &str, package: Option<&str>, scope| {
+        (function.to_string(), name.to_string(), package.map(|package| package.to_string()), scope)
+    };
+    // Calls in comments or strings, with a name which is not a literal and methods are ignored
+    assert_eq!(calls, vec![
+        call("importlib.import_module", "pkg.plugins.foo", None, ImportScope::Module),
+        call("import_module", ".sibling", Some("pkg"), ImportScope::Module),
+        call("import_module", ".current", None, ImportScope::Module),
+        call("__import__", "json", None, ImportScope::Module),
+        call("importlib.util.find_spec", "numpy", None, ImportScope::Module),
+        call("pkgutil.get_data", "pkg.data", None, ImportScope::Module),
+        call("pkgutil.resolve_name", "pkg.handlers", None, ImportScope::Module),
+        call("importlib.import_module", "lazy", None, ImportScope::Function),
+    ]);
+}
@@ -0,0 +1,46 @@
+use fast_dep::core::GraphBuilder;
+use fast_dep::error::FastDepError;
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+
+fn builder() -> GraphBuilder {
+    GraphBuilder::with_resolver(Resolver::new(vec![RES.to_string()]), false)
+}
+
+fn chain(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn test_path() {
+    let graph = builder().build_source("import diamond_top\nimport cycle_a\n", None).unwrap();
+
+    assert_eq!(
+        graph.path("<terminal>", "diamond_bottom").unwrap(),
+        Some(chain(&["<terminal>", "diamond_top", "diamond_left", "diamond_bottom"]))
+    );
+    assert_eq!(
+        graph.path("cycle_a", "cycle_c").unwrap(),
+        Some(chain(&["cycle_a", "cycle_b", "cycle_c"]))
+    );
+    assert_eq!(graph.path("diamond_bottom", "diamond_top").unwrap(), None);
+    assert_eq!(graph.path("cycle_a", "cycle_a").unwrap(), Some(chain(&["cycle_a"])));
+
+    assert!(matches!(graph.path("<terminal>", "nope"), Err(FastDepError::Graph { .. })));
+}
+
+#[test]
+fn test_all_paths() {
+    let graph = builder().build_source("import diamond_top\n", None).unwrap();
+
+    assert_eq!(graph.all_paths("diamond_top", "diamond_bottom", 10).unwrap(), vec![
+        chain(&["diamond_top", "diamond_left", "diamond_bottom"]),
+        chain(&["diamond_top", "diamond_right", "diamond_bottom"]),
+        chain(&["diamond_top", "diamond_right", "diamond_left", "diamond_bottom"]),
+    ]);
+    assert_eq!(graph.all_paths("diamond_top", "diamond_bottom", 1).unwrap(), vec![
+        chain(&["diamond_top", "diamond_left", "diamond_bottom"]),
+    ]);
+    assert!(graph.all_paths("diamond_bottom", "diamond_top", 10).unwrap().is_empty());
+}
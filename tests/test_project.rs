@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::Path;
+
+use fast_dep::core::{GraphBuilder, scan_project};
+use fast_dep::importlib::Resolver;
+
+const PROJECT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/project");
+
+fn builder() -> GraphBuilder {
+    // The project is not on the search path, it is only found through the scan
+    GraphBuilder::with_resolver(Resolver::new(vec![]), false)
+}
+
+#[test]
+fn test_scan_names() {
+    let specs = scan_project(Path::new(PROJECT)).unwrap();
+    let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+
+    // `src` layout, namespace directories and skipped directories / files which are not importable
+    assert_eq!(names, vec![
+        "proj",
+        "proj.core",
+        "proj.ns",
+        "proj.ns.plugin",
+        "proj.unused",
+        "proj.util",
+        "tests",
+        "tests.broken",
+        "tests.test_core",
+    ]);
+
+    let namespace = specs.iter().find(|spec| spec.name == "proj.ns").unwrap();
+    assert_eq!(namespace.origin, None);
+    assert!(namespace.is_package());
+
+    let package = specs.iter().find(|spec| spec.name == "proj").unwrap();
+    assert!(package.origin.as_ref().unwrap().ends_with("src/proj/__init__.py"));
+}
+
+#[test]
+fn test_scan_package_dir() {
+    let specs = scan_project(&Path::new(PROJECT).join("src/proj")).unwrap();
+    let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+
+    assert_eq!(names, vec!["proj", "proj.core", "proj.ns", "proj.ns.plugin", "proj.unused", "proj.util"]);
+}
+
+#[test]
+fn test_build_project() {
+    let graph = builder().build_project(Path::new(PROJECT)).unwrap();
+
+    assert_eq!(graph.roots.len(), 9);
+    for root in &graph.roots {
+        assert_eq!(graph.nodes.get(root).unwrap().borrow().depth, Some(0));
+    }
+
+    // Project imports resolve to the scanned files
+    let core = graph.nodes.get("proj.core").unwrap().borrow();
+    assert!(core.dependents.contains("tests.test_core"));
+    assert!(core.dependencies.contains_key("proj.util"));
+
+    // Broken files are reported without failing the build
+    assert_eq!(graph.errors.len(), 1);
+    assert_eq!(graph.errors[0].0, "tests.broken");
+}
+
+#[test]
+fn test_orphans() {
+    let graph = builder().build_project(Path::new(PROJECT)).unwrap();
+
+    assert_eq!(graph.orphans(), vec![
+        "proj.ns",
+        "proj.ns.plugin",
+        "proj.unused",
+        "tests",
+        "tests.broken",
+        "tests.test_core",
+    ]);
+}
+
+#[test]
+fn test_skipped_dir_packages() {
+    let dir = std::env::temp_dir().join(format!("fast_dep_skipped_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("build")).unwrap();
+    fs::create_dir_all(dir.join("venv")).unwrap();
+    fs::write(dir.join("build/__init__.py"), "").unwrap();
+    fs::write(dir.join("build/steps.py"), "").unwrap();
+    fs::write(dir.join("venv/installed.py"), "").unwrap();
+
+    // `build` is a package of the project, `venv` is not
+    let specs = scan_project(&dir).unwrap();
+    let names: Vec<&str> = specs.iter().map(|spec| spec.name.as_str()).collect();
+    assert_eq!(names, vec!["build", "build.steps"]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_project_specs_not_cached() {
+    let cache_dir = std::env::temp_dir().join(format!("fast_dep_project_cache_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&cache_dir);
+
+    let mut first = builder().with_cache_dir(&cache_dir).unwrap();
+    first.build_project(Path::new(PROJECT)).unwrap();
+
+    // The project is not on the search path of a later build
+    let mut second = builder().with_cache_dir(&cache_dir).unwrap();
+    let graph = second.build_source("import proj\n", None).unwrap();
+    assert!(graph.unresolved.iter().any(|unresolved| unresolved.name == "proj"));
+
+    fs::remove_dir_all(&cache_dir).unwrap();
+}
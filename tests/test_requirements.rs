@@ -0,0 +1,127 @@
+use std::path::Path;
+
+use fast_dep::core::{DeclaredDependencies, DepGraph, GraphBuilder, normalize_name};
+use fast_dep::error::FastDepError;
+use fast_dep::importlib::Resolver;
+
+const PROJECT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/deps_project");
+const SITE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/site-packages");
+
+fn build() -> DepGraph {
+    let resolver = Resolver::new(vec![PROJECT.to_string(), SITE.to_string()]);
+    GraphBuilder::with_resolver(resolver, false).build_project(Path::new(PROJECT)).unwrap()
+}
+
+fn declared() -> DeclaredDependencies {
+    DeclaredDependencies::from_pyproject(&Path::new(PROJECT).join("pyproject.toml")).unwrap()
+}
+
+#[test]
+fn test_normalize_name() {
+    assert_eq!(normalize_name("Foo_Bar.baz"), "foo-bar-baz");
+    assert_eq!(normalize_name("foo--bar"), "foo-bar");
+}
+
+#[test]
+fn test_pyproject() {
+    let declared = declared();
+
+    assert_eq!(declared.project.as_deref(), Some("deps-project"));
+    let names: Vec<&String> = declared.dependencies.keys().collect();
+    assert_eq!(names, vec!["fakepkg", "unused-dist"]);
+    assert_eq!(declared.dependencies["fakepkg"].0, "FakePkg");
+}
+
+#[test]
+fn test_requirements() {
+    let mut declared = DeclaredDependencies::default();
+    declared.add_requirements(&Path::new(PROJECT).join("requirements-dev.txt")).unwrap();
+
+    // Included with `-r`, options are skipped, `-e .` is named by its `pyproject.toml`
+    let names: Vec<&String> = declared.dependencies.keys().collect();
+    assert_eq!(names, vec!["deps-project", "legacy", "pytest"]);
+}
+
+#[test]
+fn test_requirement_lines() {
+    let dir = std::env::temp_dir().join(format!("fast_dep_lines_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("lib")).unwrap();
+    std::fs::write(dir.join("lib/pyproject.toml"), "[project]\nname = \"local_lib\"\n").unwrap();
+    std::fs::write(dir.join("requirements.txt"), concat!(
+        "alpha==1.0\t# tab before the comment\n",
+        "beta ; python_version < \"3.8\"# no space before the comment\n",
+        "-e git+https://example.com/repo.git#egg=gamma&subdirectory=src\n",
+        "--editable=./lib\n",
+        "-e ./missing\n",
+        "\t# indented comment\n",
+    )).unwrap();
+
+    let mut declared = DeclaredDependencies::default();
+    declared.add_requirements(&dir.join("requirements.txt")).unwrap();
+    let names: Vec<&String> = declared.dependencies.keys().collect();
+    assert_eq!(names, vec!["alpha", "beta", "gamma", "local-lib"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_included_requirements() {
+    let dir = std::env::temp_dir().join(format!("fast_dep_included_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // Including each other, without a space after the option
+    std::fs::write(dir.join("a.txt"), "-rb.txt\nalpha\n").unwrap();
+    std::fs::write(dir.join("b.txt"), "--requirement=a.txt\nbeta\n--requirement c.txt\n").unwrap();
+    std::fs::write(dir.join("c.txt"), "gamma\n-r b.txt\n").unwrap();
+
+    let mut declared = DeclaredDependencies::default();
+    declared.add_requirements(&dir.join("a.txt")).unwrap();
+    let names: Vec<&String> = declared.dependencies.keys().collect();
+    assert_eq!(names, vec!["alpha", "beta", "gamma"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_invalid_pyproject() {
+    let dir = std::env::temp_dir().join(format!("fast_dep_requirements_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("pyproject.toml");
+    std::fs::write(&path, "[project]\ndependencies = \"requests\"\n").unwrap();
+
+    let err = DeclaredDependencies::from_pyproject(&path).unwrap_err();
+    assert!(matches!(err, FastDepError::Parse { .. }));
+
+    std::fs::write(&path, "[project\n").unwrap();
+    let err = DeclaredDependencies::from_pyproject(&path).unwrap_err();
+    assert!(matches!(err, FastDepError::Parse { .. }));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_check_dependencies() {
+    let graph = build();
+    let report = graph.check_dependencies(&declared());
+
+    // `legacy_mod` is also imported by `fakepkg.sub`, only direct imports of first-party modules count
+    assert_eq!(report.undeclared.len(), 1);
+    let undeclared = &report.undeclared[0];
+    assert_eq!(undeclared.distribution, "legacy");
+    assert_eq!(undeclared.module, "legacy_mod");
+    assert_eq!(undeclared.importer, "app.main");
+    assert_eq!(undeclared.edge.line, 4);
+    assert!(undeclared.edge.file.as_ref().unwrap().ends_with("app/main.py"));
+
+    assert_eq!(report.unused, vec!["unused_dist"]);
+}
+
+#[test]
+fn test_check_with_requirements() {
+    let graph = build();
+    let mut declared = declared();
+    declared.add_requirements(&Path::new(PROJECT).join("requirements-dev.txt")).unwrap();
+    let report = graph.check_dependencies(&declared);
+
+    assert!(report.undeclared.is_empty());
+    assert_eq!(report.unused, vec!["pytest", "unused_dist"]);
+}
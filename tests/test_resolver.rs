@@ -0,0 +1,85 @@
+use fast_dep::importlib::{ModuleSpec, Resolver};
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/resolver");
+
+fn resolver() -> Resolver {
+    let mut resolver = Resolver::new(vec![
+        format!("{}/site_a", RES),
+        format!("{}/site_b", RES),
+    ]);
+    resolver.builtin_modules.insert("sys".to_string());
+    resolver.frozen_modules.insert("os".to_string());
+    resolver.aliases.insert("os.path".to_string(), "posixpath".to_string());
+    resolver.frozen_modules.insert("posixpath".to_string());
+
+    resolver
+}
+
+#[test]
+fn test_regular_package() {
+    let mut resolver = resolver();
+
+    let spec = resolver.find_spec("pkg").unwrap();
+    assert_eq!(spec, ModuleSpec {
+        name: "pkg".to_string(),
+        origin: Some(format!("{}/site_a/pkg/__init__.py", RES)),
+        parent: "pkg".to_string(),
+        submodule_search_locations: Some(vec![format!("{}/site_a/pkg", RES)])
+    });
+
+    let spec = resolver.find_spec("pkg.mod").unwrap();
+    assert_eq!(spec.origin, Some(format!("{}/site_a/pkg/mod.py", RES)));
+    assert_eq!(spec.parent, "pkg");
+    assert!(!spec.is_package());
+
+    assert!(resolver.find_spec("pkg.sub").unwrap().is_package());
+
+    // Regular packages do not merge with other path entries
+    assert_eq!(resolver.find_spec("pkg.only_b"), None);
+    // Modules are not packages
+    assert_eq!(resolver.find_spec("pkg.mod.nope"), None);
+    assert_eq!(resolver.find_spec("does_not_exist"), None);
+}
+
+#[test]
+fn test_namespace_package() {
+    let mut resolver = resolver();
+
+    let spec = resolver.find_spec("ns").unwrap();
+    assert_eq!(spec.origin, None);
+    assert_eq!(spec.parent, "ns");
+    assert_eq!(spec.submodule_search_locations, Some(vec![
+        format!("{}/site_a/ns", RES),
+        format!("{}/site_b/ns", RES),
+    ]));
+
+    let spec = resolver.find_spec("ns.portion_b").unwrap();
+    assert_eq!(spec.origin, Some(format!("{}/site_b/ns/portion_b.py", RES)));
+}
+
+#[test]
+fn test_suffixes_and_precedence() {
+    let mut resolver = resolver();
+
+    // Extension modules win over source files in the same directory
+    let spec = resolver.find_spec("fast").unwrap();
+    assert_eq!(spec.origin, Some(format!("{}/site_a/fast.abi3.so", RES)));
+
+    let spec = resolver.find_spec("compiled").unwrap();
+    assert_eq!(spec.origin, Some(format!("{}/site_a/compiled.pyc", RES)));
+
+    // Earlier path entries shadow later ones
+    let spec = resolver.find_spec("single").unwrap();
+    assert_eq!(spec.origin, Some(format!("{}/site_a/single.py", RES)));
+}
+
+#[test]
+fn test_interpreter_modules() {
+    let mut resolver = resolver();
+
+    assert_eq!(resolver.find_spec("sys").unwrap().origin, Some("built-in".to_string()));
+    assert_eq!(resolver.find_spec("os").unwrap().origin, Some("frozen".to_string()));
+
+    let spec = resolver.find_spec("os.path").unwrap();
+    assert_eq!(spec.name, "posixpath");
+}
@@ -0,0 +1,78 @@
+use fast_dep::core::{DepGraph, GraphBuilder, UnresolvedImport, SCHEMA_VERSION};
+use fast_dep::error::FastDepError;
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+
+fn builder() -> GraphBuilder {
+    GraphBuilder::with_resolver(Resolver::new(vec![RES.to_string()]), false)
+}
+
+#[test]
+fn test_round_trip() {
+    let graph = builder().build_source("import app\nimport app.missing\nimport app.edges\n", None).unwrap();
+
+    let data = graph.to_json(false).unwrap();
+    let loaded = DepGraph::from_json(&data).unwrap();
+    assert_eq!(loaded.to_json(false).unwrap(), data);
+
+    assert_eq!(loaded.size(), graph.size());
+    assert_eq!(loaded.num_dependencies(), graph.num_dependencies());
+    // Errors are written sorted, so only their build order is lost
+    assert_eq!(loaded.errors.len(), graph.errors.len());
+    assert!(graph.errors.iter().all(|error| loaded.errors.contains(error)));
+    assert_eq!(loaded.unresolved.len(), graph.unresolved.len());
+
+    let node = loaded.nodes.get("app.edges").unwrap().borrow();
+    let original = graph.nodes.get("app.edges").unwrap().borrow();
+    assert_eq!(node.dependencies, original.dependencies);
+    assert_eq!(node.spec, original.spec);
+    assert_eq!(node.depth, original.depth);
+    assert!(loaded.nodes.get("app").unwrap().borrow().dependents.contains("app.edges"));
+    assert!(loaded.nodes.get("not_installed").unwrap().borrow().missing);
+}
+
+#[test]
+fn test_stable_output() {
+    let source = "import diamond_top\nimport cycle_a\n";
+    let first = builder().build_source(source, None).unwrap().to_json(true).unwrap();
+    let second = builder().build_source(source, None).unwrap().to_json(true).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_invalid_documents() {
+    let unsupported = format!("{{\"version\": {}, \"nodes\": [], \"edges\": []}}", SCHEMA_VERSION + 1);
+    assert!(matches!(DepGraph::from_json(&unsupported), Err(FastDepError::Serialization { .. })));
+    assert!(matches!(DepGraph::from_json("not json"), Err(FastDepError::Serialization { .. })));
+
+    let dangling = format!(
+        "{{\"version\": {}, \"nodes\": [], \"edges\": [{{\"from\": \"a\", \"to\": \"b\", \"file\": null, \"line\": 1, \
+         \"column\": 1, \"kind\": \"import\", \"asname\": null, \"level\": 0, \"scope\": \"module\", \
+         \"conditional\": false, \"type_checking\": false}}]}}",
+        SCHEMA_VERSION
+    );
+    assert!(matches!(DepGraph::from_json(&dangling), Err(FastDepError::Serialization { .. })));
+}
+
+#[test]
+fn test_stable_records() {
+    let records = [
+        ("b", "import y", "y"),
+        ("a", "import z", "z"),
+        ("a", "import x", "x"),
+    ];
+
+    let mut forward = DepGraph::new();
+    let mut backward = DepGraph::new();
+    for (importer, text, name) in records {
+        forward.add_error(importer, FastDepError::graph(text.to_string()));
+        forward.add_unresolved(UnresolvedImport { name: name.to_string(), importer: importer.to_string(), text: text.to_string() });
+    }
+    for (importer, text, name) in records.into_iter().rev() {
+        backward.add_error(importer, FastDepError::graph(text.to_string()));
+        backward.add_unresolved(UnresolvedImport { name: name.to_string(), importer: importer.to_string(), text: text.to_string() });
+    }
+
+    assert_eq!(forward.to_json(false).unwrap(), backward.to_json(false).unwrap());
+}
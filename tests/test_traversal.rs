@@ -0,0 +1,97 @@
+use std::path::Path;
+
+use fast_dep::core::{BuildOptions, DepGraph, GraphBuilder, ModulePattern};
+use fast_dep::importlib::Resolver;
+
+const RES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/builder");
+const PROJECT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/res/project");
+
+fn builder(options: BuildOptions) -> GraphBuilder {
+    GraphBuilder::with_resolver(Resolver::new(vec![RES.to_string()]), false).with_options(options)
+}
+
+fn patterns(patterns: &[&str]) -> Vec<ModulePattern> {
+    patterns.iter().map(|pattern| ModulePattern::parse(pattern)).collect()
+}
+
+fn sorted_keys(graph: &DepGraph) -> Vec<String> {
+    let mut keys: Vec<String> = graph.keys().into_iter().collect();
+    keys.sort();
+    keys
+}
+
+#[test]
+fn test_patterns() {
+    let prefix = ModulePattern::parse("numpy");
+    assert!(prefix.matches("numpy"));
+    assert!(prefix.matches("numpy.linalg"));
+    assert!(!prefix.matches("numpy_financial"));
+
+    let glob = ModulePattern::parse("numpy.*");
+    assert!(!glob.matches("numpy"));
+    assert!(glob.matches("numpy.linalg.lapack"));
+
+    let glob = ModulePattern::parse("*.test?");
+    assert!(glob.matches("app.tests"));
+    assert!(glob.matches("app.sub.tests"));
+    assert!(!glob.matches("app.tests.helpers"));
+}
+
+#[test]
+fn test_exclude() {
+    let options = BuildOptions { exclude: patterns(&["diamond_left", "app.*"]), ..Default::default() };
+    let graph = builder(options).build_source("import diamond_top\nimport app.edges\nimport app.nothing\n", None).unwrap();
+
+    assert_eq!(sorted_keys(&graph), vec!["<terminal>", "app", "diamond_bottom", "diamond_right", "diamond_top"]);
+    // Excluded modules are not reported as unresolved either
+    assert!(graph.unresolved.is_empty());
+}
+
+#[test]
+fn test_stop_at() {
+    let options = BuildOptions { stop_at: patterns(&["diamond_right"]), ..Default::default() };
+    let graph = builder(options).build_source("import diamond_top\n", None).unwrap();
+
+    let right = graph.nodes.get("diamond_right").unwrap().borrow();
+    assert!(right.dependencies.is_empty());
+    // Still reached through other modules
+    assert!(graph.has_node("diamond_bottom"));
+}
+
+#[test]
+fn test_max_depth() {
+    let options = BuildOptions { max_depth: Some(3), ..Default::default() };
+    let graph = builder(options).build_module("depth_top").unwrap();
+
+    // `depth_leaf` is first found at depth 3 through `depth_long`, then at 2 through `depth_short`
+    assert_eq!(graph.nodes.get("depth_leaf").unwrap().borrow().depth, Some(2));
+    assert_eq!(graph.nodes.get("depth_end").unwrap().borrow().depth, Some(3));
+    assert!(!graph.has_node("acyclic"));
+
+    let options = BuildOptions { max_depth: Some(0), ..Default::default() };
+    let graph = builder(options).build_module("depth_top").unwrap();
+    assert_eq!(sorted_keys(&graph), vec!["depth_top"]);
+}
+
+#[test]
+fn test_parallel_limits() {
+    let options = BuildOptions {
+        stop_at: patterns(&["diamond_right"]),
+        max_depth: Some(3),
+        ..Default::default()
+    };
+    let source = "import diamond_top\nimport depth_top\n";
+    let serial = builder(options.clone()).build_source(source, None).unwrap();
+
+    let parallel = builder(BuildOptions { parallel: true, ..options }).build_source(source, None).unwrap();
+    assert_eq!(parallel.to_json(false).unwrap(), serial.to_json(false).unwrap());
+}
+
+#[test]
+fn test_exclude_project_modules() {
+    let options = BuildOptions { exclude: patterns(&["tests"]), ..Default::default() };
+    let graph = builder(options).build_project(Path::new(PROJECT)).unwrap();
+
+    assert!(graph.roots.iter().all(|root| root.starts_with("proj")));
+    assert!(graph.errors.is_empty());
+}